@@ -19,7 +19,9 @@ use std::io::{stdin, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::string::String;
 use zokrates_abi::Encode;
+use zokrates_core::command;
 use zokrates_core::compile::{check, compile, CompilationArtifacts, CompileError};
+use zokrates_core::error::ZokratesError;
 use zokrates_core::ir::{self, ProgEnum};
 use zokrates_core::proof_system::bellman::groth16::G16;
 #[cfg(feature = "libsnark")]
@@ -35,79 +37,41 @@ use zokrates_fs_resolver::FileSystemResolver;
 fn main() {
     cli().unwrap_or_else(|e| {
         println!("{}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     })
 }
 
 fn cli_generate_proof<T: Field, P: ProofSystem<T>>(
     program: ir::Prog<T>,
     sub_matches: &ArgMatches,
-) -> Result<(), String> {
+) -> Result<(), ZokratesError> {
     println!("Generating proof...");
 
-    // deserialize witness
-    let witness_path = Path::new(sub_matches.value_of("witness").unwrap());
-    let witness_file = match File::open(&witness_path) {
-        Ok(file) => file,
-        Err(why) => panic!("Couldn't open {}: {}", witness_path.display(), why),
-    };
-
-    let witness = ir::Witness::read(witness_file)
-        .map_err(|why| format!("Could not load witness: {:?}", why))?;
-
-    let pk_path = Path::new(sub_matches.value_of("proving-key-path").unwrap());
-    let proof_path = Path::new(sub_matches.value_of("proof-path").unwrap());
-
-    let pk_file = File::open(&pk_path)
-        .map_err(|why| format!("Couldn't open {}: {}", pk_path.display(), why))?;
+    let proof = command::generate_proof::<T, P>(
+        program,
+        Path::new(sub_matches.value_of("witness").unwrap()),
+        Path::new(sub_matches.value_of("proving-key-path").unwrap()),
+        Path::new(sub_matches.value_of("proof-path").unwrap()),
+    )?;
 
-    let mut pk: Vec<u8> = Vec::new();
-    let mut pk_reader = BufReader::new(pk_file);
-    pk_reader
-        .read_to_end(&mut pk)
-        .map_err(|why| format!("Couldn't read {}: {}", pk_path.display(), why))?;
-
-    let proof = P::generate_proof(program, witness, pk);
-    let mut proof_file = File::create(proof_path).unwrap();
-
-    let proof = serde_json::to_string_pretty(&proof).unwrap();
-    println!("Proof:\n{}", format!("{}", proof));
-
-    proof_file
-        .write(proof.as_bytes())
-        .map_err(|why| format!("Couldn't write to {}: {}", proof_path.display(), why))?;
+    println!("Proof:\n{}", P::serialize_proof(&proof));
 
     Ok(())
 }
 
 fn cli_export_verifier<T: Field, P: ProofSystem<T>>(
     sub_matches: &ArgMatches,
-) -> Result<(), String> {
+) -> Result<(), ZokratesError> {
     println!("Exporting verifier...");
 
-    // read vk file
-    let input_path = Path::new(sub_matches.value_of("input").unwrap());
-    let input_file = File::open(&input_path)
-        .map_err(|why| format!("Couldn't open {}: {}", input_path.display(), why))?;
-    let reader = BufReader::new(input_file);
-
-    let vk = serde_json::from_reader(reader)
-        .map_err(|why| format!("Couldn't deserialize verifying key: {}", why))?;
+    let abi = SolidityAbi::from(sub_matches.value_of("solidity-abi").unwrap())
+        .map_err(|why| ZokratesError::Other(why.to_string()))?;
 
-    let abi = SolidityAbi::from(sub_matches.value_of("solidity-abi").unwrap())?;
-
-    let verifier = P::export_solidity_verifier(vk, abi);
-
-    //write output file
-    let output_path = Path::new(sub_matches.value_of("output").unwrap());
-    let output_file = File::create(&output_path)
-        .map_err(|why| format!("Couldn't create {}: {}", output_path.display(), why))?;
-
-    let mut writer = BufWriter::new(output_file);
-
-    writer
-        .write_all(&verifier.as_bytes())
-        .map_err(|_| "Failed writing output to file.".to_string())?;
+    command::export_verifier::<T, P>(
+        Path::new(sub_matches.value_of("input").unwrap()),
+        Path::new(sub_matches.value_of("output").unwrap()),
+        abi,
+    )?;
 
     println!("Finished exporting verifier.");
     Ok(())
@@ -116,7 +80,7 @@ fn cli_export_verifier<T: Field, P: ProofSystem<T>>(
 fn cli_setup<T: Field, P: ProofSystem<T>>(
     program: ir::Prog<T>,
     sub_matches: &ArgMatches,
-) -> Result<(), String> {
+) -> Result<(), ZokratesError> {
     println!("Performing setup...");
 
     // print deserialized flattened program
@@ -124,37 +88,21 @@ fn cli_setup<T: Field, P: ProofSystem<T>>(
         println!("{}", program);
     }
 
-    // get paths for proving and verification keys
-    let pk_path = Path::new(sub_matches.value_of("proving-key-path").unwrap());
-    let vk_path = Path::new(sub_matches.value_of("verification-key-path").unwrap());
-
-    // run setup phase
-    let keypair = P::setup(program);
-
-    // write verification key
-    let mut vk_file = File::create(vk_path)
-        .map_err(|why| format!("couldn't create {}: {}", vk_path.display(), why))?;
-    vk_file
-        .write(
-            serde_json::to_string_pretty(&keypair.vk)
-                .unwrap()
-                .as_bytes(),
-        )
-        .map_err(|why| format!("couldn't write to {}: {}", vk_path.display(), why))?;
-
-    // write proving key
-    let mut pk_file = File::create(pk_path)
-        .map_err(|why| format!("couldn't create {}: {}", pk_path.display(), why))?;
-    pk_file
-        .write(keypair.pk.as_ref())
-        .map_err(|why| format!("couldn't write to {}: {}", pk_path.display(), why))?;
+    command::setup::<T, P>(
+        program,
+        Path::new(sub_matches.value_of("proving-key-path").unwrap()),
+        Path::new(sub_matches.value_of("verification-key-path").unwrap()),
+    )?;
 
     println!("Setup completed.");
 
     Ok(())
 }
 
-fn cli_compute<T: Field>(ir_prog: ir::Prog<T>, sub_matches: &ArgMatches) -> Result<(), String> {
+fn cli_compute<T: Field>(
+    ir_prog: ir::Prog<T>,
+    sub_matches: &ArgMatches,
+) -> Result<(), ZokratesError> {
     println!("Computing witness...");
 
     // print deserialized flattened program
@@ -166,17 +114,21 @@ fn cli_compute<T: Field>(ir_prog: ir::Prog<T>, sub_matches: &ArgMatches) -> Resu
     let is_abi = sub_matches.is_present("abi");
 
     if !is_stdin && is_abi {
-        return Err("ABI input as inline argument is not supported. Please use `--stdin`.".into());
+        return Err(ZokratesError::Other(
+            "ABI input as inline argument is not supported. Please use `--stdin`.".into(),
+        ));
     }
 
     let signature = match is_abi {
         true => {
             let path = Path::new(sub_matches.value_of("abi_spec").unwrap());
-            let file = File::open(&path)
-                .map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+            let file = File::open(&path).map_err(|why| {
+                ZokratesError::Io(format!("couldn't open {}: {}", path.display(), why))
+            })?;
             let mut reader = BufReader::new(file);
 
-            let abi: Abi = from_reader(&mut reader).map_err(|why| why.to_string())?;
+            let abi: Abi = from_reader(&mut reader)
+                .map_err(|why| ZokratesError::Serialization(why.to_string()))?;
 
             abi.signature()
         }
@@ -233,13 +185,13 @@ fn cli_compute<T: Field>(ir_prog: ir::Prog<T>, sub_matches: &ArgMatches) -> Resu
             }
         }
     }
-    .map_err(|e| format!("Could not parse argument: {}", e))?;
+    .map_err(|e| ZokratesError::Other(format!("Could not parse argument: {}", e)))?;
 
     let interpreter = ir::Interpreter::default();
 
     let witness = interpreter
         .execute(&ir_prog, &arguments.encode())
-        .map_err(|e| format!("Execution failed: {}", e))?;
+        .map_err(|e| ZokratesError::Witness(format!("Execution failed: {}", e)))?;
 
     use zokrates_abi::Decode;
 
@@ -250,19 +202,24 @@ fn cli_compute<T: Field>(ir_prog: ir::Prog<T>, sub_matches: &ArgMatches) -> Resu
 
     // write witness to file
     let output_path = Path::new(sub_matches.value_of("output").unwrap());
-    let output_file = File::create(&output_path)
-        .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
+    let output_file = File::create(&output_path).map_err(|why| {
+        ZokratesError::Io(format!(
+            "couldn't create {}: {}",
+            output_path.display(),
+            why
+        ))
+    })?;
 
     let writer = BufWriter::new(output_file);
 
     witness
         .write(writer)
-        .map_err(|why| format!("could not save witness: {:?}", why))?;
+        .map_err(|why| ZokratesError::Io(format!("could not save witness: {:?}", why)))?;
 
     Ok(())
 }
 
-fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
+fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), ZokratesError> {
     println!("Compiling {}\n", sub_matches.value_of("input").unwrap());
     let path = PathBuf::from(sub_matches.value_of("input").unwrap());
 
@@ -274,12 +231,23 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
 
     let hr_output_path = bin_output_path.to_path_buf().with_extension("ztf");
 
-    let file = File::open(path.clone())
-        .map_err(|why| format!("Couldn't open input file {}: {}", path.display(), why))?;
+    let file = File::open(path.clone()).map_err(|why| {
+        ZokratesError::Io(format!(
+            "Couldn't open input file {}: {}",
+            path.display(),
+            why
+        ))
+    })?;
 
     let mut reader = BufReader::new(file);
     let mut source = String::new();
-    reader.read_to_string(&mut source).unwrap();
+    reader.read_to_string(&mut source).map_err(|why| {
+        ZokratesError::Io(format!(
+            "Couldn't read input file {}: {}",
+            path.display(),
+            why
+        ))
+    })?;
 
     let fmt_error = |e: &CompileError| {
         let file = e.file().canonicalize().unwrap();
@@ -295,13 +263,13 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     let resolver = FileSystemResolver::new();
     let artifacts: CompilationArtifacts<T> =
         compile(source, path, Some(&resolver)).map_err(|e| {
-            format!(
+            ZokratesError::Other(format!(
                 "Compilation failed:\n\n{}",
                 e.0.iter()
                     .map(|e| fmt_error(e))
                     .collect::<Vec<_>>()
                     .join("\n\n")
-            )
+            ))
         })?;
 
     let program_flattened = artifacts.prog();
@@ -310,34 +278,64 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     let num_constraints = program_flattened.constraint_count();
 
     // serialize flattened program and write to binary file
-    let bin_output_file = File::create(&bin_output_path)
-        .map_err(|why| format!("Couldn't create {}: {}", bin_output_path.display(), why))?;
+    let bin_output_file = File::create(&bin_output_path).map_err(|why| {
+        ZokratesError::Io(format!(
+            "Couldn't create {}: {}",
+            bin_output_path.display(),
+            why
+        ))
+    })?;
 
     let mut writer = BufWriter::new(bin_output_file);
 
     program_flattened.serialize(&mut writer);
 
     // serialize ABI spec and write to JSON file
-    let abi_spec_file = File::create(&abi_spec_path)
-        .map_err(|why| format!("Couldn't create {}: {}", abi_spec_path.display(), why))?;
+    let abi_spec_file = File::create(&abi_spec_path).map_err(|why| {
+        ZokratesError::Io(format!(
+            "Couldn't create {}: {}",
+            abi_spec_path.display(),
+            why
+        ))
+    })?;
 
     let abi = artifacts.abi();
 
     let mut writer = BufWriter::new(abi_spec_file);
 
-    to_writer_pretty(&mut writer, &abi).map_err(|_| "Unable to write data to file.".to_string())?;
+    to_writer_pretty(&mut writer, &abi).map_err(|why| {
+        ZokratesError::Serialization(format!(
+            "Couldn't write {}: {}",
+            abi_spec_path.display(),
+            why
+        ))
+    })?;
 
     if !light {
         // write human-readable output file
-        let hr_output_file = File::create(&hr_output_path)
-            .map_err(|why| format!("Couldn't create {}: {}", hr_output_path.display(), why))?;
+        let hr_output_file = File::create(&hr_output_path).map_err(|why| {
+            ZokratesError::Io(format!(
+                "Couldn't create {}: {}",
+                hr_output_path.display(),
+                why
+            ))
+        })?;
 
         let mut hrofb = BufWriter::new(hr_output_file);
-        write!(&mut hrofb, "{}\n", program_flattened)
-            .map_err(|_| "Unable to write data to file.".to_string())?;
-        hrofb
-            .flush()
-            .map_err(|_| "Unable to flush buffer.".to_string())?;
+        write!(&mut hrofb, "{}\n", program_flattened).map_err(|why| {
+            ZokratesError::Io(format!(
+                "Couldn't write {}: {}",
+                hr_output_path.display(),
+                why
+            ))
+        })?;
+        hrofb.flush().map_err(|why| {
+            ZokratesError::Io(format!(
+                "Couldn't write {}: {}",
+                hr_output_path.display(),
+                why
+            ))
+        })?;
     }
 
     if !light {
@@ -355,16 +353,27 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     Ok(())
 }
 
-fn cli_check<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
+fn cli_check<T: Field>(sub_matches: &ArgMatches) -> Result<(), ZokratesError> {
     println!("Checking {}\n", sub_matches.value_of("input").unwrap());
     let path = PathBuf::from(sub_matches.value_of("input").unwrap());
 
-    let file = File::open(path.clone())
-        .map_err(|why| format!("Couldn't open input file {}: {}", path.display(), why))?;
+    let file = File::open(path.clone()).map_err(|why| {
+        ZokratesError::Io(format!(
+            "Couldn't open input file {}: {}",
+            path.display(),
+            why
+        ))
+    })?;
 
     let mut reader = BufReader::new(file);
     let mut source = String::new();
-    reader.read_to_string(&mut source).unwrap();
+    reader.read_to_string(&mut source).map_err(|why| {
+        ZokratesError::Io(format!(
+            "Couldn't read input file {}: {}",
+            path.display(),
+            why
+        ))
+    })?;
 
     let fmt_error = |e: &CompileError| {
         let file = e.file().canonicalize().unwrap();
@@ -379,13 +388,13 @@ fn cli_check<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
 
     let resolver = FileSystemResolver::new();
     let _ = check::<T, _>(source, path, Some(&resolver)).map_err(|e| {
-        format!(
+        ZokratesError::Other(format!(
             "Check failed:\n\n{}",
             e.0.iter()
                 .map(|e| fmt_error(e))
                 .collect::<Vec<_>>()
                 .join("\n\n")
-        )
+        ))
     })?;
 
     println!("Program checked, no errors found.");
@@ -393,27 +402,16 @@ fn cli_check<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     Ok(())
 }
 
-fn cli_verify<T: Field, P: ProofSystem<T>>(sub_matches: &ArgMatches) -> Result<(), String> {
-    let vk_path = Path::new(sub_matches.value_of("verification-key-path").unwrap());
-    let vk_file = File::open(&vk_path)
-        .map_err(|why| format!("Couldn't open {}: {}", vk_path.display(), why))?;
-
-    let vk_reader = BufReader::new(vk_file);
-    let vk = serde_json::from_reader(vk_reader)
-        .map_err(|why| format!("Couldn't deserialize verification key: {}", why))?;
-
-    let proof_path = Path::new(sub_matches.value_of("proof-path").unwrap());
-    let proof_file = File::open(&proof_path)
-        .map_err(|why| format!("Couldn't open {}: {}", proof_path.display(), why))?;
-
-    let proof_reader = BufReader::new(proof_file);
-    let proof = serde_json::from_reader(proof_reader)
-        .map_err(|why| format!("Couldn't deserialize proof: {}", why))?;
+fn cli_verify<T: Field, P: ProofSystem<T>>(sub_matches: &ArgMatches) -> Result<(), ZokratesError> {
+    let valid = command::verify::<T, P>(
+        Path::new(sub_matches.value_of("verification-key-path").unwrap()),
+        Path::new(sub_matches.value_of("proof-path").unwrap()),
+    )?;
 
     println!("Performing verification...");
     println!(
         "The verification result is: {}",
-        match P::verify(vk, proof) {
+        match valid {
             true => "PASS",
             false => "FAIL",
         }
@@ -422,7 +420,7 @@ fn cli_verify<T: Field, P: ProofSystem<T>>(sub_matches: &ArgMatches) -> Result<(
     Ok(())
 }
 
-fn cli() -> Result<(), String> {
+fn cli() -> Result<(), ZokratesError> {
     const FLATTENED_CODE_DEFAULT_PATH: &str = "out";
     const ABI_SPEC_DEFAULT_PATH: &str = "abi.json";
     const VERIFICATION_KEY_DEFAULT_PATH: &str = "verification.key";
@@ -790,12 +788,13 @@ fn cli() -> Result<(), String> {
         ("compute-witness", Some(sub_matches)) => {
             // read compiled program
             let path = Path::new(sub_matches.value_of("input").unwrap());
-            let file = File::open(&path)
-                .map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+            let file = File::open(&path).map_err(|why| {
+                ZokratesError::Io(format!("Couldn't open {}: {}", path.display(), why))
+            })?;
 
             let mut reader = BufReader::new(file);
 
-            match ProgEnum::deserialize(&mut reader)? {
+            match ProgEnum::deserialize(&mut reader).map_err(ZokratesError::Serialization)? {
                 ProgEnum::Bn128Program(p) => cli_compute(p, sub_matches)?,
                 ProgEnum::Bls12Program(p) => cli_compute(p, sub_matches)?,
             }
@@ -803,11 +802,12 @@ fn cli() -> Result<(), String> {
         ("setup", Some(sub_matches)) => {
             // read compiled program
             let path = Path::new(sub_matches.value_of("input").unwrap());
-            let file = File::open(&path)
-                .map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+            let file = File::open(&path).map_err(|why| {
+                ZokratesError::Io(format!("Couldn't open {}: {}", path.display(), why))
+            })?;
 
             let mut reader = BufReader::new(file);
-            let prog = ProgEnum::deserialize(&mut reader)?;
+            let prog = ProgEnum::deserialize(&mut reader).map_err(ZokratesError::Serialization)?;
 
             let dimensions = Dimensions::try_from((
                 sub_matches.value_of("backend").unwrap(),
@@ -865,11 +865,12 @@ fn cli() -> Result<(), String> {
         }
         ("generate-proof", Some(sub_matches)) => {
             let program_path = Path::new(sub_matches.value_of("input").unwrap());
-            let program_file = File::open(&program_path)
-                .map_err(|why| format!("Couldn't open {}: {}", program_path.display(), why))?;
+            let program_file = File::open(&program_path).map_err(|why| {
+                ZokratesError::Io(format!("Couldn't open {}: {}", program_path.display(), why))
+            })?;
 
             let mut reader = BufReader::new(program_file);
-            let prog = ProgEnum::deserialize(&mut reader)?;
+            let prog = ProgEnum::deserialize(&mut reader).map_err(ZokratesError::Serialization)?;
 
             let dimensions = Dimensions::try_from((
                 sub_matches.value_of("backend").unwrap(),
@@ -903,8 +904,9 @@ fn cli() -> Result<(), String> {
             let format = sub_matches.value_of("format").unwrap();
             let path = Path::new(sub_matches.value_of("proof-path").unwrap());
 
-            let file = File::open(&path)
-                .map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+            let file = File::open(&path).map_err(|why| {
+                ZokratesError::Io(format!("Couldn't open {}: {}", path.display(), why))
+            })?;
 
             let proof_object: Value =
                 serde_json::from_reader(file).map_err(|why| format!("{:?}", why))?;
@@ -0,0 +1,104 @@
+//! Synthesis of a large circuit, used to compare the symbol table backends:
+//!
+//! cargo bench --bench synthesis
+//! cargo bench --bench synthesis --features hashmap
+
+#![feature(test)]
+
+extern crate bellman_ce as bellman;
+extern crate test;
+extern crate zokrates_core;
+extern crate zokrates_field;
+
+use bellman::pairing::Engine;
+use bellman::{ConstraintSystem, Index, LinearCombination, SynthesisError, Variable};
+use test::Bencher;
+use zokrates_core::ir::{LinComb, Prog, ProgBuilder, QuadComb};
+use zokrates_field::Bn128Field;
+
+const VARIABLES: usize = 100_000;
+
+/// A constraint system which only allocates variables
+#[derive(Default)]
+struct Counter {
+    inputs: usize,
+    aux: usize,
+}
+
+impl<E: Engine> ConstraintSystem<E> for Counter {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.aux += 1;
+        Ok(Variable::new_unchecked(Index::Aux(self.aux)))
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inputs += 1;
+        Ok(Variable::new_unchecked(Index::Input(self.inputs)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+    {
+        a(LinearCombination::zero());
+        b(LinearCombination::zero());
+        c(LinearCombination::zero());
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self) {}
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// `x_{i + 1} = x_i * x_i + x_0` for `VARIABLES` variables
+fn program() -> Prog<Bn128Field> {
+    let mut builder = ProgBuilder::new();
+    let first = builder.private_input();
+    let mut last = first;
+    for _ in 0..VARIABLES {
+        let next = builder.variable();
+        builder.constrain(
+            QuadComb::from_linear_combinations(last.into(), last.into()),
+            LinComb::from(next) - LinComb::from(first),
+        );
+        last = next;
+    }
+    builder.output(last);
+    builder.build().unwrap()
+}
+
+#[bench]
+fn synthesize(b: &mut Bencher) {
+    let program = program();
+
+    b.iter(|| {
+        let mut cs = Counter::default();
+        program.clone().synthesize(&mut cs, None, None).unwrap();
+        cs
+    });
+}
@@ -0,0 +1,249 @@
+//! The setup, proving, verification and export steps of the toolbox on files, shared by the command line
+//! interface and by binaries embedding ZoKrates. `run_command` only supports the G16 scheme, while the steps
+//! themselves are generic over the proof system.
+
+use crate::error::ZokratesError;
+use crate::ir::{self, ProgEnum};
+use crate::proof_system::bellman::groth16::G16;
+use crate::proof_system::{Proof, ProofSystem, SolidityAbi};
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use zokrates_field::{Bls12Field, Bn128Field, Field};
+
+/// The curve to use for the commands which do not read a program
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Bn128,
+    Bls12,
+}
+
+pub enum Command {
+    /// Runs the setup for the program at `program`, writing the keys to `proving_key` and `verification_key`
+    Setup {
+        program: PathBuf,
+        proving_key: PathBuf,
+        verification_key: PathBuf,
+    },
+    /// Generates a proof for the program at `program` and its witness at `witness`, writing it to `proof`
+    Prove {
+        program: PathBuf,
+        witness: PathBuf,
+        proving_key: PathBuf,
+        proof: PathBuf,
+    },
+    /// Verifies the proof at `proof`, failing if it is not valid
+    Verify {
+        curve: Curve,
+        verification_key: PathBuf,
+        proof: PathBuf,
+    },
+    /// Exports a Solidity verifier for the verification key at `verification_key` to `output`
+    Export {
+        curve: Curve,
+        verification_key: PathBuf,
+        output: PathBuf,
+        abi: SolidityAbi,
+    },
+}
+
+/// Runs `command`, reading and writing the files it refers to
+pub fn run_command(command: Command) -> Result<(), ZokratesError> {
+    match command {
+        Command::Setup {
+            program,
+            proving_key,
+            verification_key,
+        } => match read_program(&program)? {
+            ProgEnum::Bn128Program(p) => setup::<_, G16>(p, &proving_key, &verification_key),
+            ProgEnum::Bls12Program(p) => setup::<_, G16>(p, &proving_key, &verification_key),
+        },
+        Command::Prove {
+            program,
+            witness,
+            proving_key,
+            proof,
+        } => match read_program(&program)? {
+            ProgEnum::Bn128Program(p) => {
+                generate_proof::<_, G16>(p, &witness, &proving_key, &proof).map(|_| ())
+            }
+            ProgEnum::Bls12Program(p) => {
+                generate_proof::<_, G16>(p, &witness, &proving_key, &proof).map(|_| ())
+            }
+        },
+        Command::Verify {
+            curve,
+            verification_key,
+            proof,
+        } => match match curve {
+            Curve::Bn128 => verify::<Bn128Field, G16>(&verification_key, &proof),
+            Curve::Bls12 => verify::<Bls12Field, G16>(&verification_key, &proof),
+        }? {
+            true => Ok(()),
+            false => Err(ZokratesError::Other(String::from("Proof is not valid"))),
+        },
+        Command::Export {
+            curve,
+            verification_key,
+            output,
+            abi,
+        } => match curve {
+            Curve::Bn128 => export_verifier::<Bn128Field, G16>(&verification_key, &output, abi),
+            Curve::Bls12 => export_verifier::<Bls12Field, G16>(&verification_key, &output, abi),
+        },
+    }
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, ZokratesError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|why| ZokratesError::Io(format!("Couldn't open {}: {}", path.display(), why)))
+}
+
+fn write(path: &Path, bytes: &[u8]) -> Result<(), ZokratesError> {
+    File::create(path)
+        .and_then(|mut file| file.write_all(bytes))
+        .map_err(|why| ZokratesError::Io(format!("Couldn't write to {}: {}", path.display(), why)))
+}
+
+fn read_program(path: &Path) -> Result<ProgEnum, ZokratesError> {
+    ProgEnum::deserialize(open(path)?).map_err(ZokratesError::Serialization)
+}
+
+/// Runs the setup of `P` for `program`, writing the keys to `proving_key` and `verification_key`
+pub fn setup<T: Field, P: ProofSystem<T>>(
+    program: ir::Prog<T>,
+    proving_key: &Path,
+    verification_key: &Path,
+) -> Result<(), ZokratesError> {
+    let keypair = P::setup(program);
+
+    write(verification_key, P::serialize_vk(&keypair.vk).as_bytes())?;
+    write(proving_key, &keypair.pk)
+}
+
+/// Generates a proof with `P` for `program` and the witness at `witness`, writing it to `proof` and returning it
+pub fn generate_proof<T: Field, P: ProofSystem<T>>(
+    program: ir::Prog<T>,
+    witness: &Path,
+    proving_key: &Path,
+    proof: &Path,
+) -> Result<Proof<P::ProofPoints>, ZokratesError> {
+    let witness = ir::Witness::read(open(witness)?)
+        .map_err(|why| ZokratesError::Witness(format!("Could not load witness: {:?}", why)))?;
+
+    let mut pk = vec![];
+    open(proving_key)?.read_to_end(&mut pk).map_err(|why| {
+        ZokratesError::Io(format!("Couldn't read {}: {}", proving_key.display(), why))
+    })?;
+
+    let generated = P::try_generate_proof(program, witness, pk)?;
+
+    write(proof, P::serialize_proof(&generated).as_bytes())?;
+
+    Ok(generated)
+}
+
+/// Verifies the proof at `proof` with `P` against the verification key at `verification_key`, returning whether
+/// it is valid
+pub fn verify<T: Field, P: ProofSystem<T>>(
+    verification_key: &Path,
+    proof: &Path,
+) -> Result<bool, ZokratesError> {
+    let vk = serde_json::from_reader(open(verification_key)?).map_err(|why| {
+        ZokratesError::Serialization(format!("Couldn't deserialize verification key: {}", why))
+    })?;
+    let proof = serde_json::from_reader(open(proof)?).map_err(|why| {
+        ZokratesError::Serialization(format!("Couldn't deserialize proof: {}", why))
+    })?;
+
+    Ok(P::verify(vk, proof))
+}
+
+/// Exports a Solidity verifier of `P` for the verification key at `verification_key` to `output`
+pub fn export_verifier<T: Field, P: ProofSystem<T>>(
+    verification_key: &Path,
+    output: &Path,
+    abi: SolidityAbi,
+) -> Result<(), ZokratesError> {
+    let vk = serde_json::from_reader(open(verification_key)?).map_err(|why| {
+        ZokratesError::Serialization(format!("Couldn't deserialize verification key: {}", why))
+    })?;
+
+    let verifier = P::export_solidity_verifier(vk, abi);
+
+    write(output, verifier.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Interpreter, Prog};
+    use crate::testing::identity;
+
+    #[test]
+    fn prove() {
+        let program: Prog<Bn128Field> = identity(true);
+
+        let dir = std::env::temp_dir().join(format!("zokrates_command_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name);
+
+        let mut serialized = vec![];
+        program.serialize(&mut serialized);
+        std::fs::write(path("out"), serialized).unwrap();
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+        witness
+            .write(File::create(path("witness")).unwrap())
+            .unwrap();
+
+        run_command(Command::Setup {
+            program: path("out"),
+            proving_key: path("proving.key"),
+            verification_key: path("verification.key"),
+        })
+        .unwrap();
+
+        run_command(Command::Prove {
+            program: path("out"),
+            witness: path("witness"),
+            proving_key: path("proving.key"),
+            proof: path("proof.json"),
+        })
+        .unwrap();
+        assert!(path("proof.json").exists());
+
+        run_command(Command::Verify {
+            curve: Curve::Bn128,
+            verification_key: path("verification.key"),
+            proof: path("proof.json"),
+        })
+        .unwrap();
+
+        match run_command(Command::Prove {
+            program: path("missing"),
+            witness: path("witness"),
+            proving_key: path("proving.key"),
+            proof: path("proof.json"),
+        }) {
+            Err(ZokratesError::Io(_)) => {}
+            _ => panic!("Expected an I/O error"),
+        }
+
+        std::fs::write(path("corrupt.key"), b"corrupt").unwrap();
+        match run_command(Command::Prove {
+            program: path("out"),
+            witness: path("witness"),
+            proving_key: path("corrupt.key"),
+            proof: path("proof.json"),
+        }) {
+            Err(ZokratesError::Serialization(_)) => {}
+            _ => panic!("Expected a serialization error"),
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
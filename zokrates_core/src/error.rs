@@ -0,0 +1,94 @@
+//! A unified error type for the entry points of the toolbox, each kind of failure being mapped to a
+//! stable process exit code so that scripts can tell them apart.
+
+use crate::proof_system::bellman::NoWitness;
+use bellman::SynthesisError;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum ZokratesError {
+    /// The constraint system could not be synthesized, during setup or proving
+    Synthesis(String),
+    /// The witness could not be computed, read or written
+    Witness(String),
+    /// A program, key or proof could not be serialized or deserialized
+    Serialization(String),
+    /// A file could not be opened, read or written
+    Io(String),
+    /// Any other failure, such as a compilation error or invalid arguments
+    Other(String),
+}
+
+impl ZokratesError {
+    /// Returns the exit code to terminate the process with when failing with this error
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            ZokratesError::Other(_) => 1,
+            ZokratesError::Io(_) => 2,
+            ZokratesError::Serialization(_) => 3,
+            ZokratesError::Witness(_) => 4,
+            ZokratesError::Synthesis(_) => 5,
+        }
+    }
+}
+
+impl fmt::Display for ZokratesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ZokratesError::Synthesis(ref e) => write!(f, "Synthesis failed: {}", e),
+            ZokratesError::Witness(ref e)
+            | ZokratesError::Serialization(ref e)
+            | ZokratesError::Io(ref e)
+            | ZokratesError::Other(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<String> for ZokratesError {
+    fn from(e: String) -> Self {
+        ZokratesError::Other(e)
+    }
+}
+
+impl From<io::Error> for ZokratesError {
+    fn from(e: io::Error) -> Self {
+        ZokratesError::Io(e.to_string())
+    }
+}
+
+impl From<SynthesisError> for ZokratesError {
+    fn from(e: SynthesisError) -> Self {
+        // the `Display` implementation of `SynthesisError` recurses infinitely on most variants
+        ZokratesError::Synthesis(format!("{:?}", e))
+    }
+}
+
+impl From<NoWitness> for ZokratesError {
+    fn from(e: NoWitness) -> Self {
+        ZokratesError::Witness(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(ZokratesError::from(String::from("error")).exit_code(), 1);
+        assert_eq!(
+            ZokratesError::from(io::Error::from(io::ErrorKind::NotFound)).exit_code(),
+            2
+        );
+        assert_eq!(
+            ZokratesError::Serialization(String::from("error")).exit_code(),
+            3
+        );
+        assert_eq!(ZokratesError::from(NoWitness).exit_code(), 4);
+        assert_eq!(
+            ZokratesError::from(SynthesisError::Unsatisfiable).exit_code(),
+            5
+        );
+    }
+}
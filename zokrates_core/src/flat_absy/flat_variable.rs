@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 
 // A variable in a constraint system
 // id > 0 for intermediate variables
@@ -10,13 +11,50 @@ pub struct FlatVariable {
     id: isize,
 }
 
+#[derive(Debug, PartialEq)]
+pub enum FlatVariableError {
+    /// The index wraps around to the id reserved for `~one`
+    CollidesWithOne,
+    /// The index does not fit the range of ids reserved for intermediate variables
+    OutOfRange(usize),
+    /// The string is not the display name of a variable
+    InvalidName(String),
+}
+
+impl fmt::Display for FlatVariableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FlatVariableError::CollidesWithOne => {
+                write!(f, "Variable index collides with the ~one variable")
+            }
+            FlatVariableError::OutOfRange(index) => {
+                write!(f, "Variable index {} is out of range", index)
+            }
+            FlatVariableError::InvalidName(ref name) => {
+                write!(f, "`{}` is not a valid variable name", name)
+            }
+        }
+    }
+}
+
 impl FlatVariable {
+    /// Creates the intermediate variable `_id`. Indices must be smaller than `isize::MAX`, as larger ones wrap
+    /// around to the ids reserved for `~one` and the outputs. Use `try_new` to check this.
     pub fn new(id: usize) -> Self {
         FlatVariable {
             id: 1 + id as isize,
         }
     }
 
+    /// Creates the intermediate variable `_id`, failing if `id` would collide with `~one` or an output
+    pub fn try_new(id: usize) -> Result<Self, FlatVariableError> {
+        match id {
+            std::usize::MAX => Err(FlatVariableError::CollidesWithOne),
+            id if id >= std::isize::MAX as usize => Err(FlatVariableError::OutOfRange(id)),
+            id => Ok(FlatVariable::new(id)),
+        }
+    }
+
     pub fn one() -> Self {
         FlatVariable { id: 0 }
     }
@@ -67,6 +105,37 @@ impl fmt::Display for FlatVariable {
     }
 }
 
+impl FromStr for FlatVariable {
+    type Err = FlatVariableError;
+
+    /// Parses the display name of a variable, `~one`, `~out_N` or `_N`. Unlike `try_from_human_readable`, only the
+    /// exact output of `Display` is accepted, so that parsing and displaying round-trip.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || FlatVariableError::InvalidName(s.to_string());
+        let index = |digits: &str| -> Result<usize, FlatVariableError> {
+            match digits.parse::<usize>().map_err(|_| invalid())? {
+                index if index >= std::isize::MAX as usize => {
+                    Err(FlatVariableError::OutOfRange(index))
+                }
+                index => Ok(index),
+            }
+        };
+
+        let variable = match s {
+            "~one" => FlatVariable::one(),
+            s if s.starts_with("~out_") => FlatVariable::public(index(&s["~out_".len()..])?),
+            s if s.starts_with('_') => FlatVariable::try_new(index(&s[1..])?)?,
+            _ => return Err(invalid()),
+        };
+
+        // reject leading zeros and signs, which `usize::from_str` accepts
+        match variable.to_string() == s {
+            true => Ok(variable),
+            false => Err(invalid()),
+        }
+    }
+}
+
 impl fmt::Debug for FlatVariable {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "FlatVariable(id: {})", self.id)
@@ -103,4 +172,43 @@ mod tests {
         assert_eq!(format!("{}", FlatVariable::new(0)), "_0");
         assert_eq!(format!("{}", FlatVariable::new(42)), "_42");
     }
+
+    #[test]
+    fn try_new() {
+        assert_eq!(FlatVariable::try_new(42), Ok(FlatVariable::new(42)));
+        assert_eq!(
+            FlatVariable::try_new(std::usize::MAX),
+            Err(FlatVariableError::CollidesWithOne)
+        );
+        assert_eq!(
+            FlatVariable::try_new(std::isize::MAX as usize),
+            Err(FlatVariableError::OutOfRange(std::isize::MAX as usize))
+        );
+        assert!(FlatVariable::try_new(std::isize::MAX as usize - 1).is_ok());
+    }
+
+    #[test]
+    fn from_str() {
+        for v in &[
+            FlatVariable::one(),
+            FlatVariable::public(0),
+            FlatVariable::public(42),
+            FlatVariable::new(0),
+            FlatVariable::new(42),
+        ] {
+            assert_eq!(v.to_string().parse::<FlatVariable>(), Ok(*v));
+        }
+
+        for s in &["one", "_", "_042", "_+42", "~out_-1", "x_42", "~out_42 "] {
+            assert_eq!(
+                s.parse::<FlatVariable>(),
+                Err(FlatVariableError::InvalidName(s.to_string()))
+            );
+        }
+
+        assert_eq!(
+            format!("_{}", std::isize::MAX).parse::<FlatVariable>(),
+            Err(FlatVariableError::OutOfRange(std::isize::MAX as usize))
+        );
+    }
 }
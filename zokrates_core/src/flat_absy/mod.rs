@@ -9,7 +9,7 @@ pub mod flat_parameter;
 pub mod flat_variable;
 
 pub use self::flat_parameter::FlatParameter;
-pub use self::flat_variable::FlatVariable;
+pub use self::flat_variable::{FlatVariable, FlatVariableError};
 
 use solvers::Solver;
 use std::collections::HashMap;
@@ -262,7 +262,7 @@ impl<T: Field> FlatExpression<T> {
 impl<T: Field> fmt::Display for FlatExpression<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            FlatExpression::Number(ref i) => write!(f, "{}", i),
+            FlatExpression::Number(ref i) => write!(f, "{}", i.to_compact_dec_string()),
             FlatExpression::Identifier(ref var) => write!(f, "{}", var),
             FlatExpression::Add(ref lhs, ref rhs) => write!(f, "({} + {})", lhs, rhs),
             FlatExpression::Sub(ref lhs, ref rhs) => write!(f, "({} - {})", lhs, rhs),
@@ -337,3 +337,21 @@ impl fmt::Display for Error {
         write!(f, "{}", self.message)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn display_negative() {
+        let e: FlatExpression<Bn128Field> = FlatExpression::Add(
+            box FlatExpression::Number(Bn128Field::from(-1)),
+            box FlatExpression::Mult(
+                box FlatExpression::Number(Bn128Field::from(2)),
+                box FlatExpression::Identifier(FlatVariable::new(42)),
+            ),
+        );
+        assert_eq!(e.to_string(), "((-1) + (2 * _42))");
+    }
+}
@@ -0,0 +1,207 @@
+//! A builder to assemble IR programs directly, without going through the compiler.
+//!
+//! Inputs must be declared before any statement is added, so that the arguments of the resulting
+//! program come first, in declaration order. Outputs are numbered in the order they are declared.
+//! Misuse of the builder is reported by `build`.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::gadgets::{assert_equal, boolean_constraint};
+use crate::ir::{Directive, Function, LinComb, Prog, QuadComb, Statement};
+use std::collections::HashSet;
+use std::fmt;
+use zokrates_field::Field;
+
+#[derive(Debug, PartialEq)]
+pub enum BuildError {
+    /// A statement refers to a variable which was not allocated by the builder
+    UndeclaredVariable(FlatVariable),
+    /// An input was declared after a statement was added
+    InputAfterStatement(FlatVariable),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::UndeclaredVariable(ref v) => {
+                write!(f, "Variable {} was not declared in this program", v)
+            }
+            BuildError::InputAfterStatement(ref v) => write!(
+                f,
+                "Input {} was declared after a statement, but inputs must be declared before any statement",
+                v
+            ),
+        }
+    }
+}
+
+pub struct ProgBuilder<T> {
+    arguments: Vec<FlatVariable>,
+    private: Vec<bool>,
+    returns: Vec<FlatVariable>,
+    statements: Vec<Statement<T>>,
+    next_id: usize,
+    /// The first misuse of the builder, reported by `build`
+    error: Option<BuildError>,
+}
+
+impl<T: Field> Default for ProgBuilder<T> {
+    fn default() -> Self {
+        ProgBuilder {
+            arguments: vec![],
+            private: vec![],
+            returns: vec![],
+            statements: vec![],
+            next_id: 0,
+            error: None,
+        }
+    }
+}
+
+impl<T: Field> ProgBuilder<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn input(&mut self, private: bool) -> FlatVariable {
+        let v = self.variable();
+        if !self.statements.is_empty() && self.error.is_none() {
+            self.error = Some(BuildError::InputAfterStatement(v));
+        }
+        self.arguments.push(v);
+        self.private.push(private);
+        v
+    }
+
+    /// Declares a new public input
+    pub fn public_input(&mut self) -> FlatVariable {
+        self.input(false)
+    }
+
+    /// Declares a new private input
+    pub fn private_input(&mut self) -> FlatVariable {
+        self.input(true)
+    }
+
+    /// Allocates a new internal variable
+    pub fn variable(&mut self) -> FlatVariable {
+        let v = FlatVariable::new(self.next_id);
+        self.next_id += 1;
+        v
+    }
+
+    /// Adds the constraint `quad == lin`
+    pub fn constrain<Q: Into<QuadComb<T>>, L: Into<LinComb<T>>>(&mut self, quad: Q, lin: L) {
+        self.statements
+            .push(Statement::Constraint(quad.into(), lin.into()));
+    }
+
+    /// Constrains `var` to be `0` or `1`
+    pub fn assert_boolean(&mut self, var: FlatVariable) {
+        self.statements.push(boolean_constraint(var));
+    }
+
+    /// Constrains `a` and `b` to be equal
+    pub fn assert_eq<A: Into<LinComb<T>>, B: Into<LinComb<T>>>(&mut self, a: A, b: B) {
+        self.statements.push(assert_equal(a.into(), b.into()));
+    }
+
+    /// Adds a directive, used to compute the values of its outputs during witness generation
+    pub fn directive(&mut self, directive: Directive<T>) {
+        self.statements.push(Statement::Directive(directive));
+    }
+
+    /// Declares a new output equal to `var`, and returns the output variable
+    pub fn output(&mut self, var: FlatVariable) -> FlatVariable {
+        let out = FlatVariable::public(self.returns.len());
+        self.returns.push(out);
+        self.constrain(var, out);
+        out
+    }
+
+    /// Assembles the program, checking that the builder was used correctly and that all variables it refers to
+    /// were declared
+    pub fn build(self) -> Result<Prog<T>, BuildError> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        let declared: HashSet<FlatVariable> = (0..self.next_id)
+            .map(FlatVariable::new)
+            .chain(self.returns.iter().cloned())
+            .chain(std::iter::once(FlatVariable::one()))
+            .collect();
+
+        for statement in &self.statements {
+            let variables: Vec<FlatVariable> = match statement {
+                Statement::Constraint(quad, lin) => quad
+                    .left
+                    .0
+                    .iter()
+                    .chain(quad.right.0.iter())
+                    .chain(lin.0.iter())
+                    .map(|(v, _)| *v)
+                    .collect(),
+                Statement::Directive(d) => d
+                    .inputs
+                    .iter()
+                    .flat_map(|i| i.left.0.iter().chain(i.right.0.iter()))
+                    .map(|(v, _)| *v)
+                    .chain(d.outputs.iter().cloned())
+                    .collect(),
+            };
+
+            if let Some(v) = variables.into_iter().find(|v| !declared.contains(v)) {
+                return Err(BuildError::UndeclaredVariable(v));
+            }
+        }
+
+        Ok(Prog {
+            main: Function {
+                id: String::from("main"),
+                statements: self.statements,
+                arguments: self.arguments,
+                returns: self.returns,
+            },
+            private: self.private,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn identity() {
+        let mut builder = ProgBuilder::new();
+        let a = builder.private_input();
+        builder.output(a);
+
+        let expected: Prog<Bn128Field> = crate::testing::identity(true);
+
+        assert_eq!(builder.build(), Ok(expected));
+    }
+
+    #[test]
+    fn undeclared_variable() {
+        let mut builder = ProgBuilder::<Bn128Field>::new();
+        let a = builder.public_input();
+        builder.constrain(a, FlatVariable::new(42));
+
+        assert_eq!(
+            builder.build(),
+            Err(BuildError::UndeclaredVariable(FlatVariable::new(42)))
+        );
+    }
+
+    #[test]
+    fn input_after_statement() {
+        let mut builder = ProgBuilder::<Bn128Field>::new();
+        let a = builder.public_input();
+        builder.output(a);
+        let b = builder.private_input();
+
+        assert_eq!(builder.build(), Err(BuildError::InputAfterStatement(b)));
+    }
+}
@@ -0,0 +1,148 @@
+//! Comparison of the constraints of two programs
+
+use crate::ir::{CanonicalLinComb, CanonicalQuadComb, LinComb, Prog, QuadComb};
+use std::collections::BTreeMap;
+use std::fmt;
+use zokrates_field::Field;
+
+type Constraint<T> = (QuadComb<T>, LinComb<T>);
+type NormalConstraint<T> = (CanonicalQuadComb<T>, CanonicalLinComb<T>);
+
+/// The constraints which differ between two programs, in normal form.
+/// Constraints appearing several times are counted with their multiplicity.
+#[derive(Debug, PartialEq)]
+pub struct ConstraintDiff<T: Field> {
+    pub added: Vec<Constraint<T>>,
+    pub removed: Vec<Constraint<T>>,
+    pub common: Vec<Constraint<T>>,
+}
+
+impl<T: Field> ConstraintDiff<T> {
+    /// Returns true if both programs have the same constraints
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<T: Field> fmt::Display for ConstraintDiff<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (quad, lin) in &self.removed {
+            writeln!(f, "- {} == {}", quad, lin)?;
+        }
+        for (quad, lin) in &self.added {
+            writeln!(f, "+ {} == {}", quad, lin)?;
+        }
+        write!(
+            f,
+            "{} added, {} removed, {} in common",
+            self.added.len(),
+            self.removed.len(),
+            self.common.len()
+        )
+    }
+}
+
+fn normal_constraints<T: Field>(p: &Prog<T>) -> Vec<NormalConstraint<T>> {
+    p.constraints()
+        .map(|(quad, lin)| {
+            (
+                quad.clone().into_normal_form(),
+                lin.clone().into_canonical(),
+            )
+        })
+        .collect()
+}
+
+impl<T: Field> Prog<T> {
+    /// Compares the constraints of this program with the ones of `other`, constraints being equal if they
+    /// only differ in the order of their terms or of their factors. Entries keep the order of the program
+    /// they come from.
+    pub fn diff(&self, other: &Prog<T>) -> ConstraintDiff<T> {
+        let ours = normal_constraints(self);
+        let theirs = normal_constraints(other);
+
+        let mut counts: BTreeMap<&NormalConstraint<T>, isize> = BTreeMap::new();
+        for c in &ours {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+
+        let mut added = vec![];
+        let mut common = vec![];
+        for c in &theirs {
+            let count = counts.entry(c).or_insert(0);
+            *count -= 1;
+            match *count >= 0 {
+                true => common.push(c),
+                false => added.push(c),
+            }
+        }
+
+        let mut removed = vec![];
+        for c in ours.iter().rev() {
+            let count = counts.get_mut(c).unwrap();
+            if *count > 0 {
+                *count -= 1;
+                removed.push(c);
+            }
+        }
+        removed.reverse();
+
+        let into_constraint = |(quad, lin): &NormalConstraint<T>| -> Constraint<T> {
+            (quad.clone().into(), lin.clone().into())
+        };
+
+        ConstraintDiff {
+            added: added.into_iter().map(into_constraint).collect(),
+            removed: removed.into_iter().map(into_constraint).collect(),
+            common: common.into_iter().map(into_constraint).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::FlatVariable;
+    use crate::ir::Statement;
+    use crate::testing::identity;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn one_added() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let mut other = program.clone();
+        other.main.statements.push(Statement::Constraint(
+            QuadComb::from_linear_combinations(
+                FlatVariable::new(0).into(),
+                FlatVariable::new(0).into(),
+            ),
+            FlatVariable::new(0).into(),
+        ));
+
+        let diff = program.diff(&other);
+
+        assert_eq!(
+            diff.added,
+            vec![(
+                QuadComb::from_linear_combinations(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::new(0).into()
+                ),
+                FlatVariable::new(0).into()
+            )]
+        );
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.common.len(), 1);
+        assert_eq!(
+            diff.to_string(),
+            "+ (1 * _0) * (1 * _0) == 1 * _0\n1 added, 0 removed, 1 in common"
+        );
+
+        let reverse = other.diff(&program);
+        assert!(reverse.added.is_empty());
+        assert_eq!(reverse.removed, diff.added);
+
+        assert!(program.diff(&program).is_empty());
+    }
+}
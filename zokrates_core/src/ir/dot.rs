@@ -0,0 +1,77 @@
+//! Export of programs as Graphviz graphs, to visualize which variables each constraint depends on
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::Prog;
+use std::collections::BTreeSet;
+use zokrates_field::Field;
+
+impl<T: Field> Prog<T> {
+    /// Returns a DOT graph with a node for each variable and each constraint of this program, and an edge from each
+    /// constraint to the variables it refers to. Public inputs are drawn as boxes and outputs as double circles.
+    /// Directives are not represented. The result can be rendered with `dot -Tpng`.
+    pub fn to_dot(&self) -> String {
+        let public_inputs: BTreeSet<FlatVariable> = self
+            .input_signature()
+            .into_iter()
+            .filter(|(_, private)| !private)
+            .map(|(v, _)| v)
+            .collect();
+
+        let mut res = vec![String::from("digraph circuit {")];
+
+        for v in self.variables(true) {
+            let style = match v {
+                v if v.is_output() => " [shape=doublecircle]",
+                v if public_inputs.contains(&v) => " [shape=box]",
+                _ => "",
+            };
+            res.push(format!("  \"{}\"{};", v, style));
+        }
+
+        for (index, (quad, lin)) in self.constraints().enumerate() {
+            res.push(format!(
+                "  \"c{}\" [shape=diamond, label=\"{} == {}\"];",
+                index, quad, lin
+            ));
+
+            let variables: BTreeSet<FlatVariable> = quad
+                .left
+                .0
+                .iter()
+                .chain(quad.right.0.iter())
+                .chain(lin.0.iter())
+                .map(|(v, _)| *v)
+                .collect();
+
+            for v in variables {
+                res.push(format!("  \"c{}\" -> \"{}\";", index, v));
+            }
+        }
+
+        res.push(String::from("}"));
+        res.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::increment;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn one() {
+        let program: Prog<Bn128Field> = increment();
+
+        let dot = program.to_dot();
+
+        assert!(dot.starts_with("digraph circuit {"));
+        assert!(dot.contains("\"_42\" [shape=box];"));
+        assert!(dot.contains("\"~out_0\" [shape=doublecircle];"));
+        assert!(dot.contains("\"~one\";"));
+        assert!(dot.contains("\"c0\" [shape=diamond"));
+        for v in &["_42", "~one", "~out_0"] {
+            assert!(dot.contains(&format!("\"c0\" -> \"{}\";", v)));
+        }
+    }
+}
@@ -184,6 +184,18 @@ impl<T: Field> LinComb<T> {
     pub fn reduce(self) -> Self {
         self.into_canonical().into()
     }
+
+    /// Merges the terms of each variable and removes the terms with a zero coefficient in place, sorting the
+    /// remaining terms by variable as `reduce` does
+    pub fn normalize(&mut self) {
+        *self = std::mem::replace(self, LinComb::zero()).reduce();
+    }
+
+    /// Returns true if this linear combination is in the form produced by `normalize`
+    pub fn is_normalized(&self) -> bool {
+        self.0.iter().all(|(_, coeff)| *coeff != T::zero())
+            && self.0.windows(2).all(|w| w[0].0 < w[1].0)
+    }
 }
 
 impl<T: Field> QuadComb<T> {
@@ -197,6 +209,20 @@ impl<T: Field> QuadComb<T> {
     pub fn reduce(self) -> Self {
         self.into_canonical().into()
     }
+
+    /// Returns the canonical form of this quadratic combination with its factors sorted, so that
+    /// `a * b` and `b * a` have the same normal form
+    pub fn into_normal_form(self) -> CanonicalQuadComb<T> {
+        let left = self.left.into_canonical();
+        let right = self.right.into_canonical();
+        match left <= right {
+            true => CanonicalQuadComb { left, right },
+            false => CanonicalQuadComb {
+                left: right,
+                right: left,
+            },
+        }
+    }
 }
 
 impl<T: Field> fmt::Display for LinComb<T> {
@@ -259,6 +285,27 @@ impl<T: Field> Mul<&T> for LinComb<T> {
     }
 }
 
+/// The error returned when dividing a linear combination by zero
+#[derive(Debug, PartialEq)]
+pub struct DivError;
+
+impl fmt::Display for DivError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cannot divide a linear combination by zero")
+    }
+}
+
+impl<T: Field> LinComb<T> {
+    /// Returns this linear combination with each coefficient multiplied by the inverse of `c`, failing if `c` is
+    /// zero. Unlike the `/` operator, the division is checked.
+    pub fn div_constant(&self, c: &T) -> Result<LinComb<T>, DivError> {
+        match *c != T::zero() {
+            true => Ok(self.clone() / c),
+            false => Err(DivError),
+        }
+    }
+}
+
 impl<T: Field> Div<&T> for LinComb<T> {
     type Output = LinComb<T>;
 
@@ -309,6 +356,44 @@ mod tests {
             assert_eq!(c, LinComb(expected_vec));
         }
 
+        #[test]
+        fn div_constant() {
+            let a: LinComb<Bn128Field> = LinComb::summand(3, FlatVariable::new(42))
+                + LinComb::summand(4, FlatVariable::new(21));
+            let two = Bn128Field::from(2);
+
+            let half = a.div_constant(&two).unwrap();
+            assert_eq!(
+                half.0[0],
+                (FlatVariable::new(42), Bn128Field::from(3) / two.clone())
+            );
+            assert_eq!(half.0[1], (FlatVariable::new(21), Bn128Field::from(2)));
+            assert_eq!(half.clone() * &two, a);
+            assert_eq!(half.0[0].1.clone() * &two, Bn128Field::from(3));
+
+            assert_eq!(a.div_constant(&Bn128Field::from(0)), Err(DivError));
+        }
+
+        #[test]
+        fn normalize() {
+            let mut a: LinComb<Bn128Field> = LinComb::summand(3, FlatVariable::new(42))
+                + LinComb::summand(2, FlatVariable::new(21))
+                + LinComb::summand(0, FlatVariable::new(7))
+                - LinComb::summand(3, FlatVariable::new(42))
+                + LinComb::summand(2, FlatVariable::new(21));
+            assert!(!a.is_normalized());
+
+            a.normalize();
+            assert!(a.is_normalized());
+            assert_eq!(a, LinComb::summand(4, FlatVariable::new(21)));
+
+            let mut b: LinComb<Bn128Field> =
+                LinComb::from(FlatVariable::new(42)) - LinComb::from(FlatVariable::new(42));
+            b.normalize();
+            assert!(b.is_zero());
+            assert!(b.is_normalized());
+        }
+
         #[test]
         fn display() {
             let a: LinComb<Bn128Field> =
@@ -317,6 +402,13 @@ mod tests {
             let zero: LinComb<Bn128Field> = LinComb::zero();
             assert_eq!(&zero.to_string(), "0");
         }
+
+        #[test]
+        fn display_negative() {
+            let a: LinComb<Bn128Field> = LinComb::summand(-1, FlatVariable::new(42))
+                + LinComb::summand(-2, FlatVariable::new(21));
+            assert_eq!(&a.to_string(), "(-2) * _21 + (-1) * _42");
+        }
     }
 
     mod quadratic {
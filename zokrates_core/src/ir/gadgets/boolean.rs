@@ -0,0 +1,42 @@
+use crate::flat_absy::FlatVariable;
+use crate::ir::{LinComb, QuadComb, Statement};
+use zokrates_field::Field;
+
+/// Returns the constraint `var * (var - 1) == 0`, which only holds if `var` is `0` or `1`
+pub fn boolean_constraint<T: Field>(var: FlatVariable) -> Statement<T> {
+    Statement::Constraint(
+        QuadComb::from_linear_combinations(var.into(), LinComb::from(var) - LinComb::one()),
+        LinComb::zero(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ir::{Interpreter, Prog, ProgBuilder};
+    use crate::testing::assert_prove_verify;
+    use zokrates_field::Bn128Field;
+
+    fn program() -> Prog<Bn128Field> {
+        let mut builder = ProgBuilder::new();
+        let a = builder.private_input();
+        builder.assert_boolean(a);
+        builder.output(a);
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn boolean() {
+        let program = program();
+
+        for value in 0..2 {
+            assert_prove_verify(program.clone(), &[Bn128Field::from(value)]);
+        }
+    }
+
+    #[test]
+    fn not_boolean() {
+        assert!(Interpreter::default()
+            .execute(&program(), &vec![Bn128Field::from(2)])
+            .is_err());
+    }
+}
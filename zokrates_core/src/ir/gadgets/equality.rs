@@ -0,0 +1,51 @@
+use crate::ir::{LinComb, QuadComb, Statement};
+use zokrates_field::Field;
+
+/// Returns the constraint `(a - b) * 1 == 0`, which only holds if `a` and `b` are equal
+pub fn assert_equal<T: Field>(a: LinComb<T>, b: LinComb<T>) -> Statement<T> {
+    Statement::Constraint(
+        QuadComb::from_linear_combinations(a - b, LinComb::one()),
+        LinComb::zero(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::flat_absy::FlatVariable;
+    use crate::ir::{Interpreter, Prog, ProgBuilder};
+    use crate::testing::assert_witness_verifies;
+    use zokrates_field::Bn128Field;
+
+    fn program() -> (Prog<Bn128Field>, FlatVariable) {
+        let mut builder = ProgBuilder::new();
+        let a = builder.private_input();
+        let b = builder.private_input();
+        builder.assert_eq(a, b);
+        builder.output(a);
+        (builder.build().unwrap(), b)
+    }
+
+    #[test]
+    fn equal() {
+        let (program, _) = program();
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42), Bn128Field::from(42)])
+            .unwrap();
+        assert_eq!(program.check_constraints(&witness), Ok(()));
+
+        assert_witness_verifies(program, witness);
+    }
+
+    #[test]
+    fn not_equal() {
+        let (program, b) = program();
+
+        let mut witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42), Bn128Field::from(42)])
+            .unwrap();
+        witness.0.insert(b, Bn128Field::from(41));
+
+        assert_eq!(program.check_constraints(&witness), Err(vec![0]));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::flat_absy::FlatVariable;
+use crate::ir::gadgets::{poseidon, select};
+use crate::ir::ProgBuilder;
+use zokrates_field::Field;
+
+/// Constrains `leaf` to be included in the Merkle tree with root `root`, where each node is the `poseidon` hash of
+/// its two children. `path` holds the siblings from the leaf up to the root, and `directions` holds for each level
+/// `1` if the current node is the right child and `0` if it is the left child. The direction bits are constrained
+/// to be boolean here.
+///
+/// # Panics
+///
+/// Panics if `path` and `directions` have different lengths.
+pub fn merkle_path<T: Field>(
+    leaf: FlatVariable,
+    path: &[FlatVariable],
+    directions: &[FlatVariable],
+    root: FlatVariable,
+    builder: &mut ProgBuilder<T>,
+) {
+    assert_eq!(
+        path.len(),
+        directions.len(),
+        "Expected one direction for each sibling"
+    );
+
+    let node = path
+        .iter()
+        .zip(directions)
+        .fold(leaf, |node, (sibling, direction)| {
+            builder.assert_boolean(*direction);
+            let left = select(*direction, (*sibling).into(), node.into(), builder);
+            let right = select(*direction, node.into(), (*sibling).into(), builder);
+            poseidon(&[left, right], builder)
+        });
+
+    builder.assert_eq(node, root);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::gadgets::poseidon_hash;
+    use crate::ir::{Interpreter, Prog};
+    use crate::testing::assert_prove_verify;
+    use zokrates_field::Bn128Field;
+
+    fn program() -> (Prog<Bn128Field>, Vec<FlatVariable>) {
+        let mut builder = ProgBuilder::new();
+        let root = builder.public_input();
+        let leaf = builder.private_input();
+        let path: Vec<_> = (0..2).map(|_| builder.private_input()).collect();
+        let directions: Vec<_> = (0..2).map(|_| builder.private_input()).collect();
+        merkle_path(leaf, &path, &directions, root, &mut builder);
+        (builder.build().unwrap(), path)
+    }
+
+    #[test]
+    fn membership() {
+        let (program, path) = program();
+
+        let leaf = Bn128Field::from(3);
+        let siblings = vec![Bn128Field::from(4), Bn128Field::from(5)];
+        // the leaf is the right child of its parent, which is the left child of the root
+        let parent = poseidon_hash(&[siblings[0].clone(), leaf.clone()]);
+        let root = poseidon_hash(&[parent, siblings[1].clone()]);
+
+        let inputs = vec![
+            root,
+            leaf,
+            siblings[0].clone(),
+            siblings[1].clone(),
+            Bn128Field::from(1),
+            Bn128Field::from(0),
+        ];
+
+        let mut witness = Interpreter::default().execute(&program, &inputs).unwrap();
+        assert_eq!(program.check_constraints(&witness), Ok(()));
+
+        witness.0.insert(path[1], Bn128Field::from(6));
+        assert!(program.check_constraints(&witness).is_err());
+
+        assert_prove_verify(program, &inputs);
+    }
+
+    #[test]
+    fn wrong_direction() {
+        let (program, _) = program();
+
+        let leaf = Bn128Field::from(3);
+        let siblings = vec![Bn128Field::from(4), Bn128Field::from(5)];
+        let parent = poseidon_hash(&[siblings[0].clone(), leaf.clone()]);
+        let root = poseidon_hash(&[parent, siblings[1].clone()]);
+
+        let inputs = vec![
+            root,
+            leaf,
+            siblings[0].clone(),
+            siblings[1].clone(),
+            Bn128Field::from(0),
+            Bn128Field::from(0),
+        ];
+
+        assert!(Interpreter::default().execute(&program, &inputs).is_err());
+    }
+}
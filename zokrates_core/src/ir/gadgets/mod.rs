@@ -0,0 +1,23 @@
+//! Reusable constraint patterns, emitted into a `ProgBuilder`
+
+mod boolean;
+mod equality;
+mod merkle;
+mod nonzero;
+mod poseidon;
+mod poseidon_constants;
+mod select;
+mod unpack;
+mod verifier;
+
+pub use self::boolean::boolean_constraint;
+pub use self::equality::assert_equal;
+pub use self::merkle::merkle_path;
+pub use self::nonzero::assert_nonzero;
+pub use self::poseidon::{poseidon, poseidon_hash, POSEIDON_MAX_INPUTS};
+pub use self::select::select;
+pub use self::unpack::unpack;
+pub use self::verifier::{
+    assert_proof_points_on_curve, verify_proof_in_circuit, CircuitVerifyingKey, CurveParameters,
+    G2PointVariables, PointVariables, ProofVariables, RecursionError,
+};
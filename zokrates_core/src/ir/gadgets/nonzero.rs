@@ -0,0 +1,52 @@
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Directive, LinComb, ProgBuilder, QuadComb};
+use crate::solvers::Solver;
+use zokrates_field::Field;
+
+/// Constrains `x` to be different from zero, by allocating its inverse and constraining `x * x_inv == 1`. The
+/// inverse is computed during witness generation, which fails with `Error::DivisionByZero` if `x` is zero.
+pub fn assert_nonzero<T: Field>(x: FlatVariable, builder: &mut ProgBuilder<T>) {
+    let inverse = builder.variable();
+
+    builder.directive(Directive {
+        inputs: vec![LinComb::one().into(), x.into()],
+        outputs: vec![inverse],
+        solver: Solver::Div,
+    });
+
+    builder.constrain(
+        QuadComb::from_linear_combinations(x.into(), inverse.into()),
+        LinComb::one(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Error, Interpreter, Prog};
+    use crate::testing::assert_prove_verify;
+    use zokrates_field::Bn128Field;
+
+    fn program() -> Prog<Bn128Field> {
+        let mut builder = ProgBuilder::new();
+        let x = builder.private_input();
+        assert_nonzero(x, &mut builder);
+        builder.output(x);
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn nonzero() {
+        assert_prove_verify(program(), &[Bn128Field::from(42)]);
+    }
+
+    #[test]
+    fn zero() {
+        assert_eq!(
+            Interpreter::default()
+                .execute(&program(), &vec![Bn128Field::from(0)])
+                .unwrap_err(),
+            Error::DivisionByZero
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use super::poseidon_constants::{MDS, ROUND_CONSTANTS};
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Directive, LinComb, ProgBuilder, QuadComb};
+use crate::solvers::Solver;
+use lazy_static::lazy_static;
+use num::Zero;
+use zokrates_field::{Bn128Field, Field, Pow};
+
+// the number of full rounds, half of which are applied before the partial rounds and half after
+const FULL_ROUNDS: usize = 8;
+// the number of partial rounds for a state of 2 to 5 elements, following the Poseidon paper for the bn128 field
+const PARTIAL_ROUNDS: [usize; 4] = [56, 57, 56, 60];
+
+/// The maximum number of inputs hashed by `poseidon`
+pub const POSEIDON_MAX_INPUTS: usize = PARTIAL_ROUNDS.len();
+
+// the Poseidon instance for a state of `inputs + 1` elements, with the reference round constants and MDS matrix
+struct Parameters {
+    partial_rounds: usize,
+    round_constants: Vec<Vec<Bn128Field>>,
+    mds: Vec<Vec<Bn128Field>>,
+}
+
+fn parse(s: &str) -> Bn128Field {
+    Bn128Field::try_from_str(s.trim_start_matches("0x"), 16).unwrap()
+}
+
+impl Parameters {
+    fn new(inputs: usize) -> Self {
+        let width = inputs + 1;
+
+        let round_constants = ROUND_CONSTANTS[inputs - 1]
+            .chunks(width)
+            .map(|round| round.iter().map(|c| parse(c)).collect())
+            .collect();
+
+        let mds = MDS[inputs - 1]
+            .iter()
+            .map(|row| row.iter().map(|m| parse(m)).collect())
+            .collect();
+
+        Parameters {
+            partial_rounds: PARTIAL_ROUNDS[inputs - 1],
+            round_constants,
+            mds,
+        }
+    }
+
+    fn is_full_round(&self, round: usize) -> bool {
+        round < FULL_ROUNDS / 2 || round >= FULL_ROUNDS / 2 + self.partial_rounds
+    }
+}
+
+lazy_static! {
+    static ref PARAMETERS: Vec<Parameters> =
+        (1..=POSEIDON_MAX_INPUTS).map(Parameters::new).collect();
+}
+
+// the parameters for hashing `inputs` elements of `T`, which must be the bn128 field
+fn parameters<T: Field>(inputs: usize) -> &'static Parameters {
+    assert!(
+        inputs >= 1 && inputs <= POSEIDON_MAX_INPUTS,
+        "Poseidon hashes between 1 and {} inputs, found {}",
+        POSEIDON_MAX_INPUTS,
+        inputs
+    );
+    assert_eq!(
+        T::name(),
+        Bn128Field::name(),
+        "Poseidon is only defined on the bn128 field"
+    );
+
+    &PARAMETERS[inputs - 1]
+}
+
+// converts between two representations of the bn128 field
+fn convert<T: Field, U: Field>(x: &U) -> T {
+    T::from_byte_vector(x.into_byte_vector())
+}
+
+/// Computes the Poseidon hash of `inputs` outside of a circuit, with the parameters used by `poseidon`
+///
+/// # Panics
+///
+/// Panics if `inputs` is empty or has more than `POSEIDON_MAX_INPUTS` elements, or if `T` is not the bn128 field.
+pub fn poseidon_hash<T: Field>(inputs: &[T]) -> T {
+    let parameters = parameters::<T>(inputs.len());
+
+    let mut state: Vec<Bn128Field> = Some(Bn128Field::zero())
+        .into_iter()
+        .chain(inputs.iter().map(convert))
+        .collect();
+
+    for (round, constants) in parameters.round_constants.iter().enumerate() {
+        for (s, c) in state.iter_mut().zip(constants) {
+            *s = s.clone() + c;
+        }
+
+        let sboxes = match parameters.is_full_round(round) {
+            true => state.len(),
+            false => 1,
+        };
+        for s in state.iter_mut().take(sboxes) {
+            *s = s.clone().pow(5);
+        }
+
+        state = parameters
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&state)
+                    .fold(Bn128Field::zero(), |acc, (m, s)| acc + m.clone() * s)
+            })
+            .collect();
+    }
+
+    convert(&state[0])
+}
+
+/// Returns a new variable equal to the Poseidon hash of `inputs`, with the `x^5` S-box, 8 full rounds, and the
+/// number of partial rounds recommended for a state of `inputs.len() + 1` elements on the bn128 field. The
+/// hash is computed during witness generation, and checked by three constraints for each S-box.
+///
+/// The round constants and the MDS matrix are those of the reference implementation, so the result matches
+/// circomlib's `poseidon`.
+///
+/// # Panics
+///
+/// Panics if `inputs` is empty or has more than `POSEIDON_MAX_INPUTS` elements, or if `T` is not the bn128 field.
+pub fn poseidon<T: Field>(inputs: &[FlatVariable], builder: &mut ProgBuilder<T>) -> FlatVariable {
+    let parameters = parameters::<T>(inputs.len());
+
+    let out = builder.variable();
+    builder.directive(Directive {
+        inputs: inputs.iter().map(|i| (*i).into()).collect(),
+        outputs: vec![out],
+        solver: Solver::Poseidon(inputs.len()),
+    });
+
+    let mut state: Vec<LinComb<T>> = Some(LinComb::zero())
+        .into_iter()
+        .chain(inputs.iter().map(|i| (*i).into()))
+        .collect();
+
+    for (round, constants) in parameters.round_constants.iter().enumerate() {
+        for (s, c) in state.iter_mut().zip(constants) {
+            *s = s.clone() + LinComb::one() * &convert(c);
+        }
+
+        let sboxes = match parameters.is_full_round(round) {
+            true => state.len(),
+            false => 1,
+        };
+        for s in state.iter_mut().take(sboxes) {
+            let square = builder.variable();
+            builder.constrain(
+                QuadComb::from_linear_combinations(s.clone(), s.clone()),
+                square,
+            );
+            let fourth = builder.variable();
+            builder.constrain(
+                QuadComb::from_linear_combinations(square.into(), square.into()),
+                fourth,
+            );
+            let fifth = builder.variable();
+            builder.constrain(
+                QuadComb::from_linear_combinations(fourth.into(), s.clone()),
+                fifth,
+            );
+            *s = fifth.into();
+        }
+
+        state = parameters
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&state)
+                    .fold(LinComb::zero(), |acc, (m, s)| acc + s.clone() * &convert(m))
+                    .reduce()
+            })
+            .collect();
+    }
+
+    builder.constrain(state[0].clone(), out);
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Interpreter, Prog};
+    use crate::testing::assert_prove_verify;
+    use zokrates_field::Bn128Field;
+
+    fn program() -> Prog<Bn128Field> {
+        let mut builder = ProgBuilder::new();
+        let a = builder.private_input();
+        let b = builder.private_input();
+        let hash = poseidon(&[a, b], &mut builder);
+        builder.output(hash);
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn hash_two_inputs() {
+        let program = program();
+
+        // three constraints for each S-box of the 8 full rounds and the 57 partial rounds of a 3-element state,
+        // the check of the directive output and the output constraint
+        assert_eq!(program.constraints().count(), 3 * (8 * 3 + 57) + 2);
+
+        let inputs = vec![Bn128Field::from(1), Bn128Field::from(2)];
+        let witness = Interpreter::default().execute(&program, &inputs).unwrap();
+        assert_eq!(witness.return_values(), vec![poseidon_hash(&inputs)]);
+        assert_ne!(
+            poseidon_hash(&inputs),
+            poseidon_hash(&[Bn128Field::from(2), Bn128Field::from(1)])
+        );
+
+        assert_prove_verify(program, &inputs);
+    }
+
+    #[test]
+    fn reference_vectors() {
+        let hash = |inputs: &[u32]| {
+            poseidon_hash(
+                &inputs
+                    .iter()
+                    .map(|i| Bn128Field::from(*i))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let expected =
+            |hex: &str| Bn128Field::try_from_str(hex.trim_start_matches("0x"), 16).unwrap();
+
+        // the outputs of circomlib's `poseidon`, which are the first elements of the permutations of
+        // `[0, 1]`, `[0, 1, 2]` and `[0, 1, 2, 3, 4]` given as test vectors by the reference implementation
+        assert_eq!(
+            hash(&[1]),
+            expected("0x29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133")
+        );
+        assert_eq!(
+            hash(&[1, 2]),
+            expected("0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a")
+        );
+        assert_eq!(
+            hash(&[1, 2, 3, 4]),
+            expected("0x299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Poseidon is only defined on the bn128 field")]
+    fn other_field() {
+        poseidon_hash(&[zokrates_field::Bls12Field::from(1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Poseidon hashes between 1 and 4 inputs, found 0")]
+    fn no_inputs() {
+        poseidon(&[], &mut ProgBuilder::<Bn128Field>::new());
+    }
+}
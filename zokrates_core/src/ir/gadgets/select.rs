@@ -0,0 +1,80 @@
+use crate::flat_absy::FlatVariable;
+use crate::ir::{LinComb, ProgBuilder, QuadComb};
+use zokrates_field::Field;
+
+/// Returns a new variable equal to `a` if `cond` is `1` and to `b` if `cond` is `0`, by constraining it to
+/// `cond * (a - b) + b`. The product is assigned to an intermediate variable first, so that both variables can be
+/// computed during witness generation.
+///
+/// `cond` is not constrained to be boolean here, which is left to the caller, for example with `boolean_constraint`.
+pub fn select<T: Field>(
+    cond: FlatVariable,
+    a: LinComb<T>,
+    b: LinComb<T>,
+    builder: &mut ProgBuilder<T>,
+) -> FlatVariable {
+    let product = builder.variable();
+    builder.constrain(
+        QuadComb::from_linear_combinations(cond.into(), a - b.clone()),
+        product,
+    );
+
+    let out = builder.variable();
+    builder.constrain(LinComb::from(product) + b, out);
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Interpreter, Prog};
+    use crate::testing::assert_witness_verifies;
+    use zokrates_field::Bn128Field;
+
+    fn program() -> Prog<Bn128Field> {
+        let mut builder = ProgBuilder::new();
+        let cond = builder.private_input();
+        let a = builder.private_input();
+        let b = builder.private_input();
+        builder.assert_boolean(cond);
+        let out = select(cond, a.into(), b.into(), &mut builder);
+        builder.output(out);
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn select_branches() {
+        let program = program();
+
+        for (cond, expected) in vec![(1, 42), (0, 51)] {
+            let witness = Interpreter::default()
+                .execute(
+                    &program,
+                    &vec![
+                        Bn128Field::from(cond),
+                        Bn128Field::from(42),
+                        Bn128Field::from(51),
+                    ],
+                )
+                .unwrap();
+            assert_eq!(witness.return_values(), vec![Bn128Field::from(expected)]);
+
+            assert_witness_verifies(program.clone(), witness);
+        }
+    }
+
+    #[test]
+    fn not_boolean_condition() {
+        assert!(Interpreter::default()
+            .execute(
+                &program(),
+                &vec![
+                    Bn128Field::from(2),
+                    Bn128Field::from(42),
+                    Bn128Field::from(51)
+                ]
+            )
+            .is_err());
+    }
+}
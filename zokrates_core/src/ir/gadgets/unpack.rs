@@ -0,0 +1,83 @@
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Directive, LinComb, ProgBuilder};
+use crate::solvers::Solver;
+use zokrates_field::Field;
+
+/// Decomposes `var` into `bits` boolean variables, most significant bit first, and constrains their
+/// weighted sum to equal `var`. A value which does not fit in `bits` bits fails this constraint.
+///
+/// The decomposition is only unique if `2^bits` is smaller than the field modulus.
+pub fn unpack<T: Field>(
+    var: FlatVariable,
+    bits: usize,
+    builder: &mut ProgBuilder<T>,
+) -> Vec<FlatVariable> {
+    let outputs: Vec<_> = (0..bits).map(|_| builder.variable()).collect();
+
+    builder.directive(Directive {
+        inputs: vec![var.into()],
+        outputs: outputs.clone(),
+        solver: Solver::Bits(bits),
+    });
+
+    for bit in &outputs {
+        builder.assert_boolean(*bit);
+    }
+
+    let sum = outputs
+        .iter()
+        .enumerate()
+        .fold(LinComb::zero(), |acc, (i, bit)| {
+            acc + LinComb::summand(T::from(2).pow(bits - i - 1), *bit)
+        });
+    builder.constrain(sum, var);
+
+    outputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Error, Interpreter, Prog};
+    use crate::testing::assert_witness_verifies;
+    use zokrates_field::Bn128Field;
+
+    fn program() -> (Prog<Bn128Field>, Vec<FlatVariable>) {
+        let mut builder = ProgBuilder::new();
+        let a = builder.private_input();
+        let bits = unpack(a, 4, &mut builder);
+        builder.output(a);
+        (builder.build().unwrap(), bits)
+    }
+
+    #[test]
+    fn unpack_constant() {
+        let (program, bits) = program();
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(5)])
+            .unwrap();
+
+        assert_eq!(
+            bits.iter()
+                .map(|b| witness.0[b].clone())
+                .collect::<Vec<_>>(),
+            vec![0, 1, 0, 1]
+                .into_iter()
+                .map(Bn128Field::from)
+                .collect::<Vec<_>>()
+        );
+
+        assert_witness_verifies(program, witness);
+    }
+
+    #[test]
+    fn too_few_bits() {
+        let (program, _) = program();
+
+        match Interpreter::default().execute(&program, &vec![Bn128Field::from(16)]) {
+            Err(Error::UnsatisfiedConstraint { .. }) => {}
+            _ => panic!("16 should not fit in 4 bits"),
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! Verification of a proof inside a circuit, for proof composition.
+//!
+//! This is a skeleton: it fixes the shape of the interface, but the pairing check is not emitted yet, so
+//! `verify_proof_in_circuit` fails with `RecursionError::Unsupported` once it has emitted the constraints which are
+//! already possible: the well-formedness of the proof, also available as `assert_proof_points_on_curve`, and the
+//! accumulation of the public inputs into `vk_x`. Their limitations are:
+//! * the curve `y^2 = x^3 + b` and its twist are taken over the field of the circuit itself, which only holds
+//!   for toy parameters, as the curves used in practice are defined over another field
+//! * `b` is a point of the twist `y^2 = x^3 + b'` over the quadratic extension `F[u] / (u^2 - nonresidue)`, and
+//!   only its membership of the twist is enforced, not that of the subgroup of prime order
+//! * the accumulation uses incomplete addition formulas, so witness generation fails when two added points share
+//!   their x-coordinate, and the points of the key must not be of order two
+
+use super::{select, unpack};
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Directive, LinComb, ProgBuilder, QuadComb};
+use crate::proof_system::write_wrong_input_count;
+use crate::solvers::Solver;
+use std::fmt;
+use zokrates_field::Field;
+
+/// The coordinates of a point of the curve, as variables of the circuit
+#[derive(Debug, Clone, Copy)]
+pub struct PointVariables {
+    pub x: FlatVariable,
+    pub y: FlatVariable,
+}
+
+/// The coordinates of a point of the twist, as variables of the circuit. Each coordinate is given as `(c0, c1)`
+/// for `c0 + c1 * u`.
+#[derive(Debug, Clone, Copy)]
+pub struct G2PointVariables {
+    pub x: (FlatVariable, FlatVariable),
+    pub y: (FlatVariable, FlatVariable),
+}
+
+/// A proof, as variables of the circuit
+#[derive(Debug, Clone, Copy)]
+pub struct ProofVariables {
+    pub a: PointVariables,
+    pub b: G2PointVariables,
+    pub c: PointVariables,
+}
+
+/// The curve of the inner proof and its twist, which are constants of the circuit
+#[derive(Debug, Clone)]
+pub struct CurveParameters<T> {
+    /// The constant `b` in the curve equation `y^2 = x^3 + b`
+    pub b: T,
+    /// The quadratic non-residue defining the extension field `F[u] / (u^2 - nonresidue)` of the twist
+    pub nonresidue: T,
+    /// The constant `b'` in the twist equation `y^2 = x^3 + b'`, as `(c0, c1)`
+    pub twist_b: (T, T),
+}
+
+/// The verifying key of the inner proof, which is a constant of the circuit
+#[derive(Debug, Clone)]
+pub struct CircuitVerifyingKey<T> {
+    pub curve: CurveParameters<T>,
+    /// The points used to accumulate the public inputs, the first one not being associated with any input
+    pub ic: Vec<(T, T)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RecursionError {
+    WrongInputCount {
+        expected: usize,
+        found: usize,
+    },
+    /// The verifying key has no point for the constant term
+    MalformedKey,
+    /// The pairing check cannot be emitted yet
+    Unsupported,
+}
+
+impl fmt::Display for RecursionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RecursionError::WrongInputCount { expected, found } => {
+                write_wrong_input_count(f, expected, found)
+            }
+            RecursionError::MalformedKey => {
+                write!(f, "Verification key has no point for the constant term")
+            }
+            RecursionError::Unsupported => {
+                write!(f, "Pairing checks inside a circuit are not supported yet")
+            }
+        }
+    }
+}
+
+type Fp2<T> = (LinComb<T>, LinComb<T>);
+
+/// Returns the product of `a` and `b` in `F[u] / (u^2 - nonresidue)`, introducing a variable for each of the four
+/// products of their components
+fn fp2_mul<T: Field>(builder: &mut ProgBuilder<T>, a: Fp2<T>, b: Fp2<T>, nonresidue: &T) -> Fp2<T> {
+    let mut product = |l: &LinComb<T>, r: &LinComb<T>| {
+        let v = builder.variable();
+        builder.constrain(QuadComb::from_linear_combinations(l.clone(), r.clone()), v);
+        LinComb::from(v)
+    };
+
+    let c0c0 = product(&a.0, &b.0);
+    let c1c1 = product(&a.1, &b.1);
+    let c0c1 = product(&a.0, &b.1);
+    let c1c0 = product(&a.1, &b.0);
+
+    (c0c0 + c1c1 * nonresidue, c0c1 + c1c0)
+}
+
+/// Constrains `p` to lie on the curve `y^2 = x^3 + b`
+fn on_curve<T: Field>(builder: &mut ProgBuilder<T>, p: PointVariables, b: &T) {
+    let x_squared = builder.variable();
+    builder.constrain(
+        QuadComb::from_linear_combinations(p.x.into(), p.x.into()),
+        x_squared,
+    );
+    let x_cubed = builder.variable();
+    builder.constrain(
+        QuadComb::from_linear_combinations(x_squared.into(), p.x.into()),
+        x_cubed,
+    );
+    builder.constrain(
+        QuadComb::from_linear_combinations(p.y.into(), p.y.into()),
+        LinComb::from(x_cubed) + LinComb::from(b.clone()),
+    );
+}
+
+/// Constrains `p` to lie on the twist `y^2 = x^3 + b'` described by `curve`
+fn on_twist<T: Field>(
+    builder: &mut ProgBuilder<T>,
+    p: G2PointVariables,
+    curve: &CurveParameters<T>,
+) {
+    let x: Fp2<T> = ((p.x.0).into(), (p.x.1).into());
+    let y: Fp2<T> = ((p.y.0).into(), (p.y.1).into());
+
+    let x_squared = fp2_mul(builder, x.clone(), x.clone(), &curve.nonresidue);
+    let x_cubed = fp2_mul(builder, x_squared, x, &curve.nonresidue);
+    let y_squared = fp2_mul(builder, y.clone(), y, &curve.nonresidue);
+
+    builder.constrain(
+        y_squared.0,
+        x_cubed.0 + LinComb::from(curve.twist_b.0.clone()),
+    );
+    builder.constrain(
+        y_squared.1,
+        x_cubed.1 + LinComb::from(curve.twist_b.1.clone()),
+    );
+}
+
+type Point<T> = (LinComb<T>, LinComb<T>);
+
+/// Returns `2 * p` for a constant point `p` of a curve `y^2 = x^3 + b`
+fn double<T: Field>(p: &(T, T)) -> (T, T) {
+    let lambda = T::from(3) * p.0.clone() * p.0.clone() / (T::from(2) * p.1.clone());
+    let x = lambda.clone() * lambda.clone() - T::from(2) * p.0.clone();
+    let y = lambda * (p.0.clone() - x.clone()) - p.1.clone();
+    (x, y)
+}
+
+/// Returns `p + q` for a point `p` and a constant point `q` of a curve `y^2 = x^3 + b`, introducing a variable for
+/// the slope and for each of the two products. Witness generation fails if `p` and `q` share their x-coordinate.
+fn add_constant<T: Field>(builder: &mut ProgBuilder<T>, p: Point<T>, q: &(T, T)) -> Point<T> {
+    let dx = LinComb::from(q.0.clone()) - p.0.clone();
+    let dy = LinComb::from(q.1.clone()) - p.1.clone();
+
+    let lambda = builder.variable();
+    builder.directive(Directive {
+        inputs: vec![dy.clone().into(), dx.clone().into()],
+        outputs: vec![lambda],
+        solver: Solver::Div,
+    });
+    builder.constrain(QuadComb::from_linear_combinations(lambda.into(), dx), dy);
+
+    let lambda_squared = builder.variable();
+    builder.constrain(
+        QuadComb::from_linear_combinations(lambda.into(), lambda.into()),
+        lambda_squared,
+    );
+    let x = LinComb::from(lambda_squared) - p.0.clone() - LinComb::from(q.0.clone());
+
+    let product = builder.variable();
+    builder.constrain(
+        QuadComb::from_linear_combinations(lambda.into(), p.0 - x.clone()),
+        product,
+    );
+    let y = LinComb::from(product) - p.1;
+
+    (x, y)
+}
+
+/// Returns `vk_x = ic[0] + inputs[0] * ic[1] + ...`, decomposing each input into bits and adding the matching
+/// multiple of its point wherever a bit is set
+fn accumulate_inputs<T: Field>(
+    builder: &mut ProgBuilder<T>,
+    ic: &[(T, T)],
+    inputs: &[FlatVariable],
+) -> Point<T> {
+    let mut acc = (
+        LinComb::from(ic[0].0.clone()),
+        LinComb::from(ic[0].1.clone()),
+    );
+
+    for (input, point) in inputs.iter().zip(&ic[1..]) {
+        let bits = unpack(*input, T::get_required_bits(), builder);
+
+        let mut multiple = point.clone();
+        // the least significant bit first, as `unpack` returns the most significant one first
+        for bit in bits.into_iter().rev() {
+            let sum = add_constant(builder, acc.clone(), &multiple);
+            acc = (
+                select(bit, sum.0, acc.0, builder).into(),
+                select(bit, sum.1, acc.1, builder).into(),
+            );
+            multiple = double(&multiple);
+        }
+    }
+
+    acc
+}
+
+/// Emits the constraints checking that `a` and `c` lie on the curve and that `b` lies on the twist described by
+/// `curve`. See the module documentation for the limitations of these checks.
+pub fn assert_proof_points_on_curve<T: Field>(
+    builder: &mut ProgBuilder<T>,
+    curve: &CurveParameters<T>,
+    proof: &ProofVariables,
+) {
+    on_curve(builder, proof.a, &curve.b);
+    on_twist(builder, proof.b, curve);
+    on_curve(builder, proof.c, &curve.b);
+}
+
+/// Emits the constraints verifying `proof` for the public inputs `inputs` against `vk`.
+///
+/// The pairing check is not supported yet, so this fails with `RecursionError::Unsupported` after emitting the
+/// well-formedness checks of `proof` and the accumulation of `inputs`. A wrong number of inputs or a key without
+/// points is reported before any constraint is emitted.
+pub fn verify_proof_in_circuit<T: Field>(
+    builder: &mut ProgBuilder<T>,
+    vk: &CircuitVerifyingKey<T>,
+    proof: &ProofVariables,
+    inputs: &[FlatVariable],
+) -> Result<(), RecursionError> {
+    let expected = vk
+        .ic
+        .len()
+        .checked_sub(1)
+        .ok_or(RecursionError::MalformedKey)?;
+    if inputs.len() != expected {
+        return Err(RecursionError::WrongInputCount {
+            expected,
+            found: inputs.len(),
+        });
+    }
+
+    assert_proof_points_on_curve(builder, &vk.curve, proof);
+    let _vk_x = accumulate_inputs(builder, &vk.ic, inputs);
+
+    Err(RecursionError::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Interpreter;
+    use zokrates_field::Bn128Field;
+
+    fn point(builder: &mut ProgBuilder<Bn128Field>) -> PointVariables {
+        PointVariables {
+            x: builder.private_input(),
+            y: builder.private_input(),
+        }
+    }
+
+    fn g2_point(builder: &mut ProgBuilder<Bn128Field>) -> G2PointVariables {
+        G2PointVariables {
+            x: (builder.private_input(), builder.private_input()),
+            y: (builder.private_input(), builder.private_input()),
+        }
+    }
+
+    // y^2 = x^3 + 3, on which (1, 2) lies, and its twist over F[u] / (u^2 - 5), on which (1 + u, 2 + 3u) lies:
+    // x^3 = 16 + 8u and y^2 = 49 + 12u, so b' = 33 + 4u
+    fn curve() -> CurveParameters<Bn128Field> {
+        CurveParameters {
+            b: Bn128Field::from(3),
+            nonresidue: Bn128Field::from(5),
+            twist_b: (Bn128Field::from(33), Bn128Field::from(4)),
+        }
+    }
+
+    #[test]
+    fn well_formed() {
+        let mut builder = ProgBuilder::new();
+
+        let proof = ProofVariables {
+            a: point(&mut builder),
+            b: g2_point(&mut builder),
+            c: point(&mut builder),
+        };
+
+        assert_proof_points_on_curve(&mut builder, &curve(), &proof);
+
+        let program = builder.build().unwrap();
+        // three constraints for each point of the curve, and three products of four constraints and two
+        // equalities for the point of the twist
+        assert_eq!(program.constraint_count(), 2 * 3 + 3 * 4 + 2);
+
+        let inputs: Vec<_> = vec![1, 2, 1, 1, 2, 3, 1, 2]
+            .into_iter()
+            .map(Bn128Field::from)
+            .collect();
+        assert!(Interpreter::default().execute(&program, &inputs).is_ok());
+
+        // (1, 1) is not on the curve
+        let mut off_curve = inputs.clone();
+        off_curve[1] = Bn128Field::from(1);
+        assert!(Interpreter::default()
+            .execute(&program, &off_curve)
+            .is_err());
+
+        // (1 + u, 2 + 2u) is not on the twist
+        let mut off_twist = inputs;
+        off_twist[5] = Bn128Field::from(2);
+        assert!(Interpreter::default()
+            .execute(&program, &off_twist)
+            .is_err());
+    }
+
+    // `(1, 2)` and its double, on `y^2 = x^3 + 3`
+    fn ic() -> Vec<(Bn128Field, Bn128Field)> {
+        let g = (Bn128Field::from(1), Bn128Field::from(2));
+        vec![g.clone(), double(&g)]
+    }
+
+    #[test]
+    fn accumulate() {
+        let add = |p: &(Bn128Field, Bn128Field), q: &(Bn128Field, Bn128Field)| {
+            let lambda = (q.1.clone() - p.1.clone()) / (q.0.clone() - p.0.clone());
+            let x = lambda.clone() * lambda.clone() - p.0.clone() - q.0.clone();
+            let y = lambda * (p.0.clone() - x.clone()) - p.1.clone();
+            (x, y)
+        };
+
+        let mut builder = ProgBuilder::new();
+        let input = builder.private_input();
+        let vk_x = accumulate_inputs(&mut builder, &ic(), &[input]);
+        for coordinate in vec![vk_x.0, vk_x.1] {
+            let v = builder.variable();
+            builder.constrain(coordinate, v);
+            builder.output(v);
+        }
+        let program = builder.build().unwrap();
+
+        // g + 3 * (2 * g) = 4 * g + 2 * g + g
+        let g = ic()[0].clone();
+        let two_g = ic()[1].clone();
+        let expected = add(&add(&double(&two_g), &two_g), &g);
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(3)])
+            .unwrap();
+        assert_eq!(witness.return_values(), vec![expected.0, expected.1]);
+    }
+
+    #[test]
+    fn unsupported() {
+        let mut builder = ProgBuilder::<Bn128Field>::new();
+
+        let proof = ProofVariables {
+            a: point(&mut builder),
+            b: g2_point(&mut builder),
+            c: point(&mut builder),
+        };
+        let input = builder.public_input();
+
+        let vk = CircuitVerifyingKey {
+            curve: curve(),
+            ic: ic(),
+        };
+
+        assert_eq!(
+            verify_proof_in_circuit(&mut builder, &vk, &proof, &[]),
+            Err(RecursionError::WrongInputCount {
+                expected: 1,
+                found: 0
+            })
+        );
+        assert_eq!(
+            RecursionError::WrongInputCount {
+                expected: 1,
+                found: 0
+            }
+            .to_string(),
+            "Verification key expects 1 public input but 0 were provided"
+        );
+        assert_eq!(
+            verify_proof_in_circuit(
+                &mut builder,
+                &CircuitVerifyingKey {
+                    curve: curve(),
+                    ic: vec![],
+                },
+                &proof,
+                &[]
+            ),
+            Err(RecursionError::MalformedKey)
+        );
+        assert_eq!(
+            verify_proof_in_circuit(&mut builder, &vk, &proof, &[input]),
+            Err(RecursionError::Unsupported)
+        );
+
+        // only the last call emits constraints: the checks of the proof points, then the decomposition of the
+        // input and a conditional addition of three constraints and two selections for each of its bits
+        let bits = Bn128Field::get_required_bits();
+        assert_eq!(
+            builder.build().unwrap().constraint_count(),
+            2 * 3 + 3 * 4 + 2 + (bits + 1) + bits * (3 + 2 * 2)
+        );
+    }
+}
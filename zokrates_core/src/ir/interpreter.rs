@@ -1,5 +1,6 @@
 use crate::flat_absy::flat_variable::FlatVariable;
-use crate::ir::{LinComb, Prog, QuadComb, Statement, Witness};
+use crate::ir::gadgets::poseidon_hash;
+use crate::ir::{LinComb, Prog, QuadComb, Statement, Witness, WitnessSink};
 use ir::Directive;
 use solvers::Solver;
 use std::collections::BTreeMap;
@@ -8,7 +9,44 @@ use zokrates_field::Field;
 
 pub type ExecutionResult<T> = Result<Witness<T>, Error>;
 
-impl<T: Field> Prog<T> {}
+impl<T: Field> Prog<T> {
+    /// Checks that `witness` satisfies every constraint of this program, without solving any directive.
+    /// Fails with the indices of the statements whose constraint is not satisfied, including those that
+    /// refer to a variable missing from the witness.
+    pub fn check_constraints(&self, witness: &Witness<T>) -> Result<(), Vec<usize>> {
+        let unsatisfied: Vec<usize> = self
+            .main
+            .statements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, statement)| match statement {
+                Statement::Constraint(quad, lin) => {
+                    match (quad.evaluate(&witness.0), lin.evaluate(&witness.0)) {
+                        // the witness is secret
+                        (Ok(left), Ok(right)) if left.ct_eq(&right) => None,
+                        _ => Some(index),
+                    }
+                }
+                Statement::Directive(_) => None,
+            })
+            .collect();
+
+        match unsatisfied.is_empty() {
+            true => Ok(()),
+            false => Err(unsatisfied),
+        }
+    }
+
+    /// Executes this program on `inputs` with the default interpreter, passing each value to `sink` as it is
+    /// computed. See `Interpreter::execute_streaming`.
+    pub fn run_streaming<S: WitnessSink<T>>(
+        &self,
+        inputs: &[T],
+        sink: &mut S,
+    ) -> Result<(), Error> {
+        Interpreter::default().execute_streaming(self, inputs, sink)
+    }
+}
 
 pub struct Interpreter {
     /// Whether we should try to give out-of-range bit decompositions when the input is not a single summand.
@@ -34,63 +72,116 @@ impl Interpreter {
 
 impl Interpreter {
     pub fn execute<T: Field>(&self, program: &Prog<T>, inputs: &Vec<T>) -> ExecutionResult<T> {
+        let mut witness = Witness::empty();
+        self.execute_streaming(program, inputs, &mut witness)?;
+        Ok(witness)
+    }
+
+    /// Executes `program` like `execute`, passing the value of each variable to `sink` as soon as it is computed
+    /// instead of collecting them into a `Witness`.
+    ///
+    /// Values are only kept in memory until the last statement referring to them has run, so the interpreter holds
+    /// the values live at a given point of the program rather than the whole witness. It still keeps a map from
+    /// every variable to the index of its last use, so memory remains linear in the number of variables: a sink
+    /// writing values to disk saves the field elements of the witness, not all of its footprint.
+    pub fn execute_streaming<T: Field, S: WitnessSink<T>>(
+        &self,
+        program: &Prog<T>,
+        inputs: &[T],
+        sink: &mut S,
+    ) -> Result<(), Error> {
         let main = &program.main;
-        self.check_inputs(&program, &inputs)?;
+        self.check_inputs(program, inputs)?;
+
+        let mut last_use = BTreeMap::new();
+        for (index, statement) in main.statements.iter().enumerate() {
+            for v in statement.variables() {
+                last_use.insert(v, index);
+            }
+        }
+
         let mut witness = BTreeMap::new();
         witness.insert(FlatVariable::one(), T::one());
+        sink.insert(FlatVariable::one(), T::one());
         for (arg, value) in main.arguments.iter().zip(inputs.iter()) {
             witness.insert(arg.clone(), value.clone().into());
+            sink.insert(*arg, value.clone());
         }
 
-        for statement in main.statements.iter() {
-            match statement {
-                Statement::Constraint(quad, lin) => match lin.is_assignee(&witness) {
-                    true => {
-                        let val = quad.evaluate(&witness).unwrap();
-                        witness.insert(lin.0.iter().next().unwrap().0.clone(), val);
+        for (index, statement) in main.statements.iter().enumerate() {
+            self.execute_statement(statement, &mut witness, sink)?;
+
+            // drop the values no later statement refers to, keeping `~one` for the constraints using constants
+            for v in statement.variables() {
+                if v != FlatVariable::one() && last_use.get(&v) == Some(&index) {
+                    witness.remove(&v);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn execute_statement<T: Field, S: WitnessSink<T>>(
+        &self,
+        statement: &Statement<T>,
+        witness: &mut BTreeMap<FlatVariable, T>,
+        sink: &mut S,
+    ) -> Result<(), Error> {
+        match statement {
+            Statement::Constraint(quad, lin) => match lin.is_assignee(&witness) {
+                true => {
+                    let val = quad.evaluate(&witness).unwrap();
+                    let assignee = lin.0[0].0;
+                    witness.insert(assignee, val.clone());
+                    sink.insert(assignee, val);
+                }
+                false => {
+                    let lhs_value = quad.evaluate(&witness).unwrap();
+                    let rhs_value = lin.evaluate(&witness).unwrap();
+                    // the witness is secret
+                    if !lhs_value.ct_eq(&rhs_value) {
+                        return Err(Error::UnsatisfiedConstraint {
+                            left: lhs_value.to_dec_string(),
+                            right: rhs_value.to_dec_string(),
+                        });
                     }
-                    false => {
-                        let lhs_value = quad.evaluate(&witness).unwrap();
-                        let rhs_value = lin.evaluate(&witness).unwrap();
-                        if lhs_value != rhs_value {
-                            return Err(Error::UnsatisfiedConstraint {
-                                left: lhs_value.to_dec_string(),
-                                right: rhs_value.to_dec_string(),
-                            });
+                }
+            },
+            Statement::Directive(ref d) => {
+                match (&d.solver, &d.inputs, self.should_try_out_of_range) {
+                    (Solver::Bits(bitwidth), inputs, true)
+                        if inputs[0].left.0.len() > 1
+                            || inputs[0].right.0.len() > 1
+                                && *bitwidth == T::get_required_bits() =>
+                    {
+                        Self::try_solve_out_of_range(&d, witness);
+                        for o in &d.outputs {
+                            sink.insert(*o, witness[o].clone());
                         }
                     }
-                },
-                Statement::Directive(ref d) => {
-                    match (&d.solver, &d.inputs, self.should_try_out_of_range) {
-                        (Solver::Bits(bitwidth), inputs, true)
-                            if inputs[0].left.0.len() > 1
-                                || inputs[0].right.0.len() > 1
-                                    && *bitwidth == T::get_required_bits() =>
-                        {
-                            Self::try_solve_out_of_range(&d, &mut witness)
-                        }
-                        _ => {
-                            let inputs: Vec<_> = d
-                                .inputs
-                                .iter()
-                                .map(|i| i.evaluate(&witness).unwrap())
-                                .collect();
-                            match self.execute_solver(&d.solver, &inputs) {
-                                Ok(res) => {
-                                    for (i, o) in d.outputs.iter().enumerate() {
-                                        witness.insert(o.clone(), res[i].clone());
-                                    }
-                                    continue;
+                    _ => {
+                        let inputs: Vec<_> = d
+                            .inputs
+                            .iter()
+                            .map(|i| i.evaluate(&witness).unwrap())
+                            .collect();
+                        match self.execute_solver(&d.solver, &inputs) {
+                            Ok(res) => {
+                                for (i, o) in d.outputs.iter().enumerate() {
+                                    witness.insert(o.clone(), res[i].clone());
+                                    sink.insert(*o, res[i].clone());
                                 }
-                                Err(_) => return Err(Error::Solver),
-                            };
-                        }
+                            }
+                            Err(_) if d.solver == Solver::Div => return Err(Error::DivisionByZero),
+                            Err(_) => return Err(Error::Solver),
+                        };
                     }
                 }
             }
         }
 
-        Ok(Witness(witness))
+        Ok(())
     }
 
     fn try_solve_out_of_range<T: Field>(d: &Directive<T>, witness: &mut BTreeMap<FlatVariable, T>) {
@@ -123,7 +214,7 @@ impl Interpreter {
         }
     }
 
-    fn check_inputs<T: Field, U>(&self, program: &Prog<T>, inputs: &Vec<U>) -> Result<(), Error> {
+    fn check_inputs<T: Field, U>(&self, program: &Prog<T>, inputs: &[U]) -> Result<(), Error> {
         if program.main.arguments.len() == inputs.len() {
             Ok(())
         } else {
@@ -155,7 +246,8 @@ impl Interpreter {
                         res.push(T::zero());
                     }
                 }
-                assert_eq!(num, T::zero());
+                // a value which does not fit in `bit_width` bits leaves a remainder here, which makes the
+                // constraint on the sum of the bits fail
                 res
             }
             Solver::Xor => {
@@ -184,7 +276,11 @@ impl Interpreter {
                 let c = inputs[2].clone();
                 vec![a * (b - c.clone()) + c]
             }
-            Solver::Div => vec![inputs[0].clone() / inputs[1].clone()],
+            Solver::Div => match inputs[1].is_zero() {
+                true => return Err(String::from("Division by zero")),
+                false => vec![inputs[0].clone() / inputs[1].clone()],
+            },
+            Solver::Poseidon(_) => vec![poseidon_hash(inputs)],
         };
 
         assert_eq!(res.len(), expected_output_count);
@@ -202,6 +298,11 @@ impl<T: Field> LinComb<T> {
             .map(|v| v.iter().fold(T::from(0), |acc, t| acc + t)) // return the sum
     }
 
+    /// Evaluates this linear combination under `witness`, returning `None` if a variable is missing from it
+    pub fn evaluate_in(&self, witness: &Witness<T>) -> Option<T> {
+        self.evaluate(&witness.0).ok()
+    }
+
     fn is_assignee<U>(&self, witness: &BTreeMap<FlatVariable, U>) -> bool {
         self.0.iter().count() == 1
             && self.0.iter().next().unwrap().1 == T::from(1)
@@ -215,12 +316,22 @@ impl<T: Field> QuadComb<T> {
         let right = self.right.evaluate(&witness)?;
         Ok(left * right)
     }
+
+    /// Evaluates both factors of this quadratic combination under `witness`, returning `None` if a variable is
+    /// missing from it. Useful to inspect the intermediate values of a constraint.
+    pub fn evaluate_factors(&self, witness: &Witness<T>) -> Option<(T, T)> {
+        Some((
+            self.left.evaluate_in(witness)?,
+            self.right.evaluate_in(witness)?,
+        ))
+    }
 }
 
 #[derive(PartialEq, Serialize, Deserialize, Clone)]
 pub enum Error {
     UnsatisfiedConstraint { left: String, right: String },
     Solver,
+    DivisionByZero,
     WrongInputCount { expected: usize, received: usize },
 }
 
@@ -232,6 +343,7 @@ impl fmt::Display for Error {
                 ref right,
             } => write!(f, "Expected {} to equal {}", left, right),
             Error::Solver => write!(f, ""),
+            Error::DivisionByZero => write!(f, "Division by zero"),
             Error::WrongInputCount { expected, received } => write!(
                 f,
                 "Program takes {} input{} but was passed {} value{}",
@@ -294,6 +406,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn evaluate_factors() {
+        // (2 * _0 + ~one) * (_0 - _1) == _2
+        let quad: QuadComb<Bn128Field> = QuadComb::from_linear_combinations(
+            LinComb::summand(2, FlatVariable::new(0)) + LinComb::one(),
+            LinComb::from(FlatVariable::new(0)) - LinComb::from(FlatVariable::new(1)),
+        );
+        let lin: LinComb<Bn128Field> = FlatVariable::new(2).into();
+
+        let mut witness = Witness(
+            vec![
+                (FlatVariable::one(), Bn128Field::from(1)),
+                (FlatVariable::new(0), Bn128Field::from(5)),
+                (FlatVariable::new(1), Bn128Field::from(3)),
+                (FlatVariable::new(2), Bn128Field::from(22)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            quad.evaluate_factors(&witness),
+            Some((Bn128Field::from(11), Bn128Field::from(2)))
+        );
+        assert_eq!(lin.evaluate_in(&witness), Some(Bn128Field::from(22)));
+
+        witness.0.remove(&FlatVariable::new(1));
+        assert_eq!(quad.evaluate_factors(&witness), None);
+    }
+
     #[test]
     fn bits_of_one() {
         let inputs = vec![Bn128Field::from(1)];
@@ -322,4 +464,41 @@ mod tests {
         assert_eq!(res[248], Bn128Field::from(1));
         assert_eq!(res[247], Bn128Field::from(0));
     }
+
+    #[test]
+    fn streaming() {
+        use crate::ir::gadgets::unpack;
+        use crate::ir::ProgBuilder;
+
+        /// A sink recording the assignments in the order they are received
+        struct Log(Vec<(FlatVariable, Bn128Field)>);
+
+        impl WitnessSink<Bn128Field> for Log {
+            fn insert(&mut self, variable: FlatVariable, value: Bn128Field) {
+                self.0.push((variable, value));
+            }
+        }
+
+        let mut builder = ProgBuilder::new();
+        let a = builder.private_input();
+        let b = builder.public_input();
+        let bits = unpack(a, 8, &mut builder);
+        let product = builder.variable();
+        builder.constrain(
+            QuadComb::from_linear_combinations(bits[7].into(), b.into()),
+            product,
+        );
+        builder.output(product);
+        let program = builder.build().unwrap();
+
+        let inputs = vec![Bn128Field::from(201), Bn128Field::from(42)];
+
+        let mut log = Log(vec![]);
+        program.run_streaming(&inputs, &mut log).unwrap();
+
+        let witness = Interpreter::default().execute(&program, &inputs).unwrap();
+        assert_eq!(log.0.len(), witness.0.len());
+        assert_eq!(Witness(log.0.into_iter().collect()), witness);
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(42)]);
+    }
 }
@@ -0,0 +1,84 @@
+//! Labels for the public inputs of a program, which tell public arguments and return values apart
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Prog, Witness};
+use crate::proof_system::bellman::PublicInputsError;
+use std::collections::BTreeMap;
+use zokrates_field::Field;
+
+/// The label of a public input of a program
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct PublicInputLabel {
+    /// The display name of the variable holding this input, such as `_0` or `~out_1`
+    pub variable: String,
+    /// The index of the return value, for inputs which are return values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<usize>,
+    /// The source name of the variable, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl<T: Field> Prog<T> {
+    /// Returns a label for each public input of this program, in the order of `Witness::public_view`: the public
+    /// arguments, then the return values tagged with their index. Source names are taken from `names` if any.
+    pub fn public_input_labels(
+        &self,
+        names: Option<&BTreeMap<FlatVariable, String>>,
+    ) -> Vec<PublicInputLabel> {
+        let name = |v: &FlatVariable| names.and_then(|names| names.get(v)).cloned();
+
+        self.input_signature()
+            .into_iter()
+            .filter(|(_, private)| !private)
+            .map(|(v, _)| PublicInputLabel {
+                variable: v.to_string(),
+                output: None,
+                name: name(&v),
+            })
+            .chain(
+                self.main
+                    .returns
+                    .iter()
+                    .enumerate()
+                    .map(|(index, v)| PublicInputLabel {
+                        variable: v.to_string(),
+                        output: Some(index),
+                        name: name(v),
+                    }),
+            )
+            .collect()
+    }
+}
+
+impl<T: Field> Witness<T> {
+    /// Returns a JSON object holding the public values of this witness for `program`, grouped into `arguments`
+    /// and `outputs`. Each value is a decimal string, next to the fields of its label from `public_input_labels`.
+    /// Fails where `public_view` does.
+    pub fn to_grouped_json(
+        &self,
+        program: &Prog<T>,
+        names: Option<&BTreeMap<FlatVariable, String>>,
+    ) -> Result<String, PublicInputsError> {
+        let (outputs, arguments): (Vec<_>, Vec<_>) = program
+            .public_input_labels(names)
+            .into_iter()
+            .zip(self.public_view(program)?)
+            .map(|(label, value)| {
+                let mut entry = serde_json::to_value(&label).unwrap();
+                entry["value"] = serde_json::Value::String(value.to_dec_string());
+                (label.output.is_some(), entry)
+            })
+            .partition(|(is_output, _)| *is_output);
+
+        let values = |entries: Vec<(bool, serde_json::Value)>| {
+            serde_json::Value::Array(entries.into_iter().map(|(_, e)| e).collect())
+        };
+
+        Ok(serde_json::json!({
+            "arguments": values(arguments),
+            "outputs": values(outputs),
+        })
+        .to_string())
+    }
+}
@@ -0,0 +1,200 @@
+//! Direct witness computation for programs whose constraints are all linear, without running
+//! directives or the interpreter.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Prog, Statement, Witness};
+use std::collections::BTreeMap;
+use std::fmt;
+use zokrates_field::Field;
+
+#[derive(Debug, PartialEq)]
+pub enum SolveError {
+    /// The program contains a quadratic constraint or a directive
+    Nonlinear,
+    /// A constraint has more than one unknown variable when it is reached
+    Underdetermined(usize),
+    /// A constraint does not hold for the values solved so far
+    Unsatisfied(usize),
+    WrongInputCount {
+        expected: usize,
+        received: usize,
+    },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SolveError::Nonlinear => write!(f, "Program contains non-linear constraints"),
+            SolveError::Underdetermined(index) => {
+                write!(f, "Statement {} has more than one unknown variable", index)
+            }
+            SolveError::Unsatisfied(index) => write!(f, "Statement {} is not satisfied", index),
+            SolveError::WrongInputCount { expected, received } => write!(
+                f,
+                "Program takes {} input{} but was passed {} value{}",
+                expected,
+                if expected == 1 { "" } else { "s" },
+                received,
+                if received == 1 { "" } else { "s" }
+            ),
+        }
+    }
+}
+
+impl<T: Field> Prog<T> {
+    /// Computes a witness for a program made of linear constraints only, by forward substitution:
+    /// each constraint must have at most one variable which is not known yet when it is reached.
+    pub fn solve_linear(&self, inputs: &[T]) -> Result<Witness<T>, SolveError> {
+        if self.main.arguments.len() != inputs.len() {
+            return Err(SolveError::WrongInputCount {
+                expected: self.main.arguments.len(),
+                received: inputs.len(),
+            });
+        }
+
+        let mut witness = BTreeMap::new();
+        witness.insert(FlatVariable::one(), T::one());
+        witness.extend(
+            self.main
+                .arguments
+                .iter()
+                .cloned()
+                .zip(inputs.iter().cloned()),
+        );
+
+        for (index, statement) in self.main.statements.iter().enumerate() {
+            let (quad, lin) = match statement {
+                Statement::Constraint(quad, lin) => (quad, lin),
+                Statement::Directive(..) => return Err(SolveError::Nonlinear),
+            };
+
+            let left = quad.try_linear().ok_or(SolveError::Nonlinear)?;
+
+            // move everything to the left: `left - lin == 0`
+            let (unknown, known): (Vec<_>, Vec<_>) = (left - lin.clone())
+                .into_canonical()
+                .0
+                .into_iter()
+                .partition(|(v, _)| !witness.contains_key(v));
+
+            let known = known
+                .into_iter()
+                .fold(T::zero(), |acc, (v, c)| acc + c * witness[&v].clone());
+
+            match unknown.len() {
+                0 => {
+                    if known != T::zero() {
+                        return Err(SolveError::Unsatisfied(index));
+                    }
+                }
+                1 => {
+                    let (v, c) = unknown.into_iter().next().unwrap();
+                    witness.insert(v, (T::zero() - known) / c);
+                }
+                _ => return Err(SolveError::Underdetermined(index)),
+            }
+        }
+
+        Ok(Witness(witness))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Function, Interpreter, LinComb, QuadComb};
+    use crate::testing::increment;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn identity() {
+        let program: Prog<Bn128Field> = crate::testing::identity(true);
+
+        let inputs = vec![Bn128Field::from(42)];
+
+        assert_eq!(
+            program.solve_linear(&inputs),
+            Ok(Interpreter::default().execute(&program, &inputs).unwrap())
+        );
+    }
+
+    #[test]
+    fn one() {
+        let program: Prog<Bn128Field> = increment();
+
+        let inputs = vec![Bn128Field::from(3)];
+
+        let witness = program.solve_linear(&inputs).unwrap();
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(4)]);
+        assert_eq!(
+            witness,
+            Interpreter::default().execute(&program, &inputs).unwrap()
+        );
+    }
+
+    #[test]
+    fn solve_any_side() {
+        // 2 * ~out_0 == _0, with ~out_0 unknown
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    LinComb::summand(2, FlatVariable::public(0)).into(),
+                    FlatVariable::new(0).into(),
+                )],
+            },
+            private: vec![true],
+        };
+
+        let witness = program.solve_linear(&[Bn128Field::from(6)]).unwrap();
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(3)]);
+    }
+
+    #[test]
+    fn unsatisfied() {
+        // _0 == 1
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    LinComb::one(),
+                )],
+            },
+            private: vec![true],
+        };
+
+        assert_eq!(
+            program.solve_linear(&[Bn128Field::from(2)]),
+            Err(SolveError::Unsatisfied(0))
+        );
+    }
+
+    #[test]
+    fn nonlinear() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::new(0).into(),
+                    ),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            private: vec![true],
+        };
+
+        assert_eq!(
+            program.solve_linear(&[Bn128Field::from(2)]),
+            Err(SolveError::Nonlinear)
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Reduction of the constraints of a program failing on given inputs to a minimal subset which still fails
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Error, Function, Interpreter, Prog, Statement};
+use std::collections::BTreeSet;
+use zokrates_field::Field;
+
+impl<T: Field> Prog<T> {
+    /// Returns the indices of a minimal set of constraints which are still not satisfied when executing this
+    /// program on `inputs` with all other constraints removed, or `None` if the execution does not fail on an
+    /// unsatisfied constraint. The set is found by delta debugging and is minimal in that removing any constraint
+    /// from it makes the failure disappear.
+    ///
+    /// Constraints used to assign a variable during execution are never removed, as the rest of the program
+    /// depends on them. The constraints which can be removed never assign a variable, so whether one of them holds
+    /// does not depend on the others: the set returned always holds a single constraint.
+    pub fn minimize_failure(&self, inputs: &[T]) -> Option<Vec<usize>> {
+        let checks = self.checks();
+        let all: BTreeSet<usize> = checks.iter().cloned().collect();
+
+        match self.fails_with(inputs, &all, &all) {
+            true => Some(ddmin(checks, |subset| {
+                self.fails_with(inputs, &all, &subset.iter().cloned().collect())
+            })),
+            false => None,
+        }
+    }
+
+    /// Returns the indices of the constraints which the interpreter checks, as opposed to those it uses to assign
+    /// their right hand side variable
+    fn checks(&self) -> Vec<usize> {
+        let mut defined: BTreeSet<FlatVariable> = self.main.arguments.iter().cloned().collect();
+        defined.insert(FlatVariable::one());
+
+        self.main
+            .statements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, s)| match s {
+                Statement::Constraint(_, lin) => {
+                    match lin.0.len() == 1
+                        && lin.0[0].1 == T::one()
+                        && !defined.contains(&lin.0[0].0)
+                    {
+                        true => {
+                            defined.insert(lin.0[0].0);
+                            None
+                        }
+                        false => Some(index),
+                    }
+                }
+                Statement::Directive(d) => {
+                    defined.extend(d.outputs.iter().cloned());
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if executing this program on `inputs` fails on an unsatisfied constraint when only the checks
+    /// in `kept` among `checks` are enforced
+    fn fails_with(&self, inputs: &[T], checks: &BTreeSet<usize>, kept: &BTreeSet<usize>) -> bool {
+        let program = Prog {
+            main: Function {
+                id: self.main.id.clone(),
+                statements: self
+                    .main
+                    .statements
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !checks.contains(index) || kept.contains(index))
+                    .map(|(_, s)| s.clone())
+                    .collect(),
+                arguments: self.main.arguments.clone(),
+                returns: self.main.returns.clone(),
+            },
+            private: self.private.clone(),
+        };
+
+        match Interpreter::default().execute(&program, &inputs.to_vec()) {
+            Err(Error::UnsatisfiedConstraint { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Reduces `set` to a subset for which `fails` holds and which no longer fails when any element is removed, assuming
+/// `fails(&set)` holds
+fn ddmin<F: Fn(&[usize]) -> bool>(mut set: Vec<usize>, fails: F) -> Vec<usize> {
+    let mut granularity = 2;
+
+    while set.len() >= 2 {
+        let chunk_size = (set.len() + granularity - 1) / granularity;
+        let chunks: Vec<Vec<usize>> = set.chunks(chunk_size).map(|c| c.to_vec()).collect();
+
+        if let Some(chunk) = chunks.iter().find(|chunk| fails(chunk)) {
+            set = chunk.clone();
+            granularity = 2;
+            continue;
+        }
+
+        let complement = chunks
+            .iter()
+            .map(|chunk| {
+                set.iter()
+                    .filter(|i| !chunk.contains(i))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .find(|complement| fails(complement));
+
+        match complement {
+            Some(complement) => {
+                set = complement;
+                granularity = std::cmp::max(granularity - 1, 2);
+            }
+            None if granularity >= set.len() => break,
+            None => granularity = std::cmp::min(granularity * 2, set.len()),
+        }
+    }
+
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{LinComb, ProgBuilder, QuadComb};
+    use zokrates_field::Bn128Field;
+
+    fn program() -> Prog<Bn128Field> {
+        let mut builder = ProgBuilder::new();
+        let a = builder.private_input();
+        let b = builder.private_input();
+        builder.assert_eq(a, LinComb::one());
+        builder.assert_eq(b, LinComb::summand(2, FlatVariable::one()));
+        let product = builder.variable();
+        builder.constrain(
+            QuadComb::from_linear_combinations(a.into(), b.into()),
+            product,
+        );
+        builder.output(product);
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn two_broken_constraints() {
+        let program = program();
+        let inputs = vec![Bn128Field::from(3), Bn128Field::from(5)];
+
+        assert_eq!(program.checks(), vec![0, 1]);
+
+        // each broken constraint fails on its own, so a single one is enough to reproduce the failure
+        let minimal = program.minimize_failure(&inputs).unwrap();
+        assert_eq!(minimal.len(), 1);
+        assert!(minimal[0] == 0 || minimal[0] == 1);
+        let checks = program.checks().into_iter().collect();
+        assert!(program.fails_with(&inputs, &checks, &minimal.into_iter().collect()));
+        assert!(!program.fails_with(&inputs, &checks, &BTreeSet::new()));
+    }
+
+    #[test]
+    fn one_broken_constraint() {
+        assert_eq!(
+            program().minimize_failure(&[Bn128Field::from(1), Bn128Field::from(5)]),
+            Some(vec![1])
+        );
+    }
+
+    #[test]
+    fn satisfied() {
+        assert_eq!(
+            program().minimize_failure(&[Bn128Field::from(1), Bn128Field::from(2)]),
+            None
+        );
+    }
+
+    #[test]
+    fn delta_debugging() {
+        // fails when both 3 and 6 are kept
+        let minimal = ddmin((0..10).collect(), |set| {
+            set.contains(&3) && set.contains(&6)
+        });
+        assert_eq!(minimal, vec![3, 6]);
+    }
+}
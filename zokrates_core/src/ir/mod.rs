@@ -1,22 +1,43 @@
 use crate::flat_absy::flat_parameter::FlatParameter;
 use crate::flat_absy::FlatVariable;
 use crate::solvers::Solver;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use zokrates_field::Field;
 
+mod builder;
+mod diff;
+mod dot;
 mod expression;
 pub mod folder;
 mod from_flat;
+pub mod gadgets;
 mod interpreter;
+mod labels;
+mod linear_solver;
+mod minimize;
+mod r1cs;
+mod redundancy;
 mod serialize;
+mod source_map;
+mod specialize;
+mod truncate;
+mod validation;
 mod witness;
 
+pub use self::builder::{BuildError, ProgBuilder};
+pub use self::diff::ConstraintDiff;
 pub use self::expression::QuadComb;
-pub use self::expression::{CanonicalLinComb, LinComb};
+pub use self::expression::{CanonicalLinComb, CanonicalQuadComb, DivError, LinComb};
 pub use self::serialize::ProgEnum;
+pub use self::source_map::{ConstraintFailure, SourceMap, SourceSpan};
 
 pub use self::interpreter::{Error, ExecutionResult, Interpreter};
-pub use self::witness::Witness;
+pub use self::labels::PublicInputLabel;
+pub use self::linear_solver::SolveError;
+pub use self::r1cs::SparseMatrix;
+pub use self::validation::ValidationError;
+pub use self::witness::{MergeError, Witness, WitnessSink};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash)]
 pub enum Statement<T> {
@@ -46,6 +67,31 @@ impl<T: Field> Statement<T> {
     pub fn constraint<U: Into<QuadComb<T>>, V: Into<LinComb<T>>>(quad: U, lin: V) -> Self {
         Statement::Constraint(quad.into(), lin.into())
     }
+
+    /// Returns the variables this statement refers to, in order of appearance and possibly repeated
+    pub fn variables(&self) -> Vec<FlatVariable> {
+        let in_quad = |q: &QuadComb<T>| {
+            q.left
+                .0
+                .iter()
+                .chain(q.right.0.iter())
+                .map(|(v, _)| *v)
+                .collect::<Vec<_>>()
+        };
+
+        match self {
+            Statement::Constraint(quad, lin) => {
+                let mut variables = in_quad(quad);
+                variables.extend(lin.0.iter().map(|(v, _)| *v));
+                variables
+            }
+            Statement::Directive(d) => {
+                let mut variables: Vec<_> = d.inputs.iter().flat_map(in_quad).collect();
+                variables.extend(d.outputs.iter().cloned());
+                variables
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Hash)]
@@ -111,10 +157,16 @@ impl<T: Field> PartialEq for Function<T> {
     }
 }
 
-impl<T: Field> fmt::Display for Function<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl<T: Field> Function<T> {
+    /// Writes this function as its `Display` implementation does, appending ` // ` and the annotation returned by
+    /// `annotate` to each statement for which it returns one
+    pub(crate) fn write_annotated<W: fmt::Write, A: fmt::Display, F: Fn(usize) -> Option<A>>(
+        &self,
+        w: &mut W,
+        annotate: F,
+    ) -> fmt::Result {
         write!(
-            f,
+            w,
             "def {}({}) -> ({}):\n{}\n\t return {}",
             self.id,
             self.arguments
@@ -125,7 +177,11 @@ impl<T: Field> fmt::Display for Function<T> {
             self.returns.len(),
             self.statements
                 .iter()
-                .map(|s| format!("\t{}", s))
+                .enumerate()
+                .map(|(index, s)| match annotate(index) {
+                    Some(annotation) => format!("\t{} // {}", s, annotation),
+                    None => format!("\t{}", s),
+                })
                 .collect::<Vec<_>>()
                 .join("\n"),
             self.returns
@@ -137,6 +193,12 @@ impl<T: Field> fmt::Display for Function<T> {
     }
 }
 
+impl<T: Field> fmt::Display for Function<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_annotated(f, |_| None::<String>)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Prog<T> {
     pub main: Function<T>,
@@ -151,14 +213,61 @@ impl<T: Field> PartialEq for Prog<T> {
 
 impl<T: Field> Prog<T> {
     pub fn constraint_count(&self) -> usize {
+        self.constraints().count()
+    }
+
+    /// Returns an iterator over the constraints of this program, skipping directives
+    pub fn constraints(&self) -> impl Iterator<Item = (&QuadComb<T>, &LinComb<T>)> {
+        self.main.statements.iter().filter_map(|s| match s {
+            Statement::Constraint(quad, lin) => Some((quad, lin)),
+            _ => None,
+        })
+    }
+
+    /// Returns a rough estimate of the cost of proving this program, to compare circuits without proving them.
+    /// Each term of each linear combination in a constraint adds one to the estimate, as dense linear combinations
+    /// translate into more work in the multi-exponentiations performed by the prover. Directives are ignored.
+    pub fn proving_cost_estimate(&self) -> u64 {
+        self.constraints()
+            .map(|(quad, lin)| (quad.left.0.len() + quad.right.0.len() + lin.0.len()) as u64)
+            .sum()
+    }
+
+    /// Returns the degree of each constraint of this program, in order: `1` for constraints whose quadratic side is
+    /// a linear combination scaled by `~one`, and `2` for the others
+    pub fn constraint_degrees(&self) -> Vec<u8> {
+        self.constraints()
+            .map(|(quad, _)| match quad.try_linear() {
+                Some(_) => 1,
+                None => 2,
+            })
+            .collect()
+    }
+
+    /// Returns the set of variables this program refers to in its arguments, returns, constraints and
+    /// directives, including the constant `~one` variable only if `include_one` is set
+    pub fn variables(&self, include_one: bool) -> BTreeSet<FlatVariable> {
         self.main
-            .statements
+            .arguments
             .iter()
-            .filter(|s| match s {
-                Statement::Constraint(..) => true,
-                _ => false,
-            })
-            .count()
+            .chain(self.main.returns.iter())
+            .cloned()
+            .chain(self.main.statements.iter().flat_map(|s| s.variables()))
+            .filter(|v| include_one || *v != FlatVariable::one())
+            .collect()
+    }
+
+    /// Returns a hash of the serialized form of this program, which changes whenever a constraint, a directive or
+    /// the signature of the program changes
+    pub fn structural_hash(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        let mut bytes = vec![];
+        self.serialize(&mut bytes);
+
+        let mut res = [0u8; 32];
+        res.copy_from_slice(&Sha256::digest(&bytes));
+        res
     }
 
     pub fn arguments_count(&self) -> usize {
@@ -176,6 +285,81 @@ impl<T: Field> Prog<T> {
             })
             .collect()
     }
+
+    /// Returns each argument of this program along with whether it is private, in declaration order
+    pub fn input_signature(&self) -> Vec<(FlatVariable, bool)> {
+        self.main
+            .arguments
+            .iter()
+            .cloned()
+            .zip(self.private.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the private arguments of this program, in declaration order
+    pub fn private_variables(&self) -> Vec<FlatVariable> {
+        self.input_signature()
+            .into_iter()
+            .filter(|(_, private)| *private)
+            .map(|(v, _)| v)
+            .collect()
+    }
+
+    /// Returns whether this program has at least one private argument
+    pub fn has_private_inputs(&self) -> bool {
+        self.private.iter().any(|p| *p)
+    }
+
+    /// Assembles the public input vector, i.e. the public arguments followed by the return values,
+    /// from values keyed by the display name of each variable (`_0`, `~out_0`, ...)
+    pub fn public_inputs_from_map(
+        &self,
+        named: &BTreeMap<String, T>,
+    ) -> Result<Vec<T>, PublicInputsError> {
+        let public_variables: Vec<String> = self
+            .parameters()
+            .into_iter()
+            .filter(|p| !p.private)
+            .map(|p| p.id)
+            .chain(self.main.returns.iter().cloned())
+            .map(|v| v.to_string())
+            .collect();
+
+        if let Some(name) = named.keys().find(|k| !public_variables.contains(k)) {
+            return Err(PublicInputsError::Unexpected(name.clone()));
+        }
+
+        public_variables
+            .into_iter()
+            .map(|name| {
+                named
+                    .get(&name)
+                    .cloned()
+                    .ok_or(PublicInputsError::Missing(name))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PublicInputsError {
+    Missing(String),
+    Unexpected(String),
+}
+
+impl fmt::Display for PublicInputsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PublicInputsError::Missing(ref name) => {
+                write!(f, "Missing value for public input {}", name)
+            }
+            PublicInputsError::Unexpected(ref name) => write!(
+                f,
+                "Unexpected value for {}, which is not a public input",
+                name
+            ),
+        }
+    }
 }
 
 impl<T: Field> fmt::Display for Prog<T> {
@@ -204,4 +388,203 @@ mod tests {
             assert_eq!(format!("{}", c), "(1 * _42) * (1 * _42) == 1 * _42")
         }
     }
+
+    mod constraints {
+        use super::*;
+
+        #[test]
+        fn skip_directives() {
+            let program: Prog<Bn128Field> = Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: vec![FlatVariable::new(0)],
+                    returns: vec![FlatVariable::public(0)],
+                    statements: vec![
+                        Statement::Directive(Directive {
+                            inputs: vec![FlatVariable::new(0).into()],
+                            outputs: vec![FlatVariable::new(1)],
+                            solver: Solver::Bits(1),
+                        }),
+                        Statement::Constraint(
+                            FlatVariable::new(1).into(),
+                            FlatVariable::new(0).into(),
+                        ),
+                        Statement::Constraint(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::public(0).into(),
+                        ),
+                    ],
+                },
+                private: vec![false],
+            };
+
+            assert_eq!(program.constraints().count(), program.constraint_count());
+            assert_eq!(program.constraints().count(), 2);
+            assert_eq!(
+                program.constraints().next(),
+                Some((&FlatVariable::new(1).into(), &FlatVariable::new(0).into()))
+            );
+        }
+    }
+
+    mod proving_cost_estimate {
+        use super::*;
+
+        fn program(statements: Vec<Statement<Bn128Field>>) -> Prog<Bn128Field> {
+            Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: vec![FlatVariable::new(0), FlatVariable::new(1)],
+                    returns: vec![FlatVariable::public(0)],
+                    statements,
+                },
+                private: vec![false, false],
+            }
+        }
+
+        #[test]
+        fn denser_is_costlier() {
+            let sparse = program(vec![
+                Statement::Directive(Directive {
+                    inputs: vec![FlatVariable::new(0).into(), FlatVariable::new(1).into()],
+                    outputs: vec![FlatVariable::new(2)],
+                    solver: Solver::Xor,
+                }),
+                Statement::Constraint(
+                    QuadComb::from_linear_combinations(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::new(1).into(),
+                    ),
+                    FlatVariable::public(0).into(),
+                ),
+            ]);
+
+            let dense = program(vec![Statement::Constraint(
+                QuadComb::from_linear_combinations(
+                    LinComb::from(FlatVariable::new(0)) + LinComb::from(FlatVariable::new(1)),
+                    LinComb::from(FlatVariable::new(1)) + LinComb::one(),
+                ),
+                FlatVariable::public(0).into(),
+            )]);
+
+            assert_eq!(sparse.constraint_count(), dense.constraint_count());
+            assert_eq!(sparse.proving_cost_estimate(), 3);
+            assert_eq!(dense.proving_cost_estimate(), 5);
+        }
+    }
+
+    mod constraint_degrees {
+        use super::*;
+
+        #[test]
+        fn linear_and_quadratic() {
+            let program: Prog<Bn128Field> = Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: vec![FlatVariable::new(0)],
+                    returns: vec![FlatVariable::public(0)],
+                    statements: vec![
+                        Statement::Constraint(
+                            QuadComb::from_linear_combinations(
+                                FlatVariable::new(0).into(),
+                                FlatVariable::new(0).into(),
+                            ),
+                            FlatVariable::new(1).into(),
+                        ),
+                        Statement::Directive(Directive {
+                            inputs: vec![FlatVariable::new(1).into()],
+                            outputs: vec![FlatVariable::new(2)],
+                            solver: Solver::Bits(1),
+                        }),
+                        Statement::Constraint(
+                            QuadComb::from_linear_combinations(
+                                FlatVariable::new(1).into(),
+                                LinComb::one() * &Bn128Field::from(3),
+                            ),
+                            FlatVariable::new(3).into(),
+                        ),
+                        Statement::Constraint(
+                            FlatVariable::new(3).into(),
+                            FlatVariable::public(0).into(),
+                        ),
+                    ],
+                },
+                private: vec![false],
+            };
+
+            assert_eq!(program.constraint_degrees(), vec![2, 1, 1]);
+        }
+    }
+
+    mod public_inputs {
+        use super::*;
+
+        fn program() -> Prog<Bn128Field> {
+            Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: vec![FlatVariable::new(0), FlatVariable::new(1)],
+                    returns: vec![FlatVariable::public(0)],
+                    statements: vec![Statement::Constraint(
+                        (LinComb::from(FlatVariable::new(0)) + LinComb::from(FlatVariable::new(1)))
+                            .into(),
+                        FlatVariable::public(0).into(),
+                    )],
+                },
+                private: vec![false, false],
+            }
+        }
+
+        #[test]
+        fn from_map() {
+            let named = vec![
+                ("~out_0".to_string(), Bn128Field::from(5)),
+                ("_1".to_string(), Bn128Field::from(3)),
+                ("_0".to_string(), Bn128Field::from(2)),
+            ]
+            .into_iter()
+            .collect();
+
+            assert_eq!(
+                program().public_inputs_from_map(&named),
+                Ok(vec![
+                    Bn128Field::from(2),
+                    Bn128Field::from(3),
+                    Bn128Field::from(5)
+                ])
+            );
+        }
+
+        #[test]
+        fn missing() {
+            let named = vec![
+                ("~out_0".to_string(), Bn128Field::from(5)),
+                ("_0".to_string(), Bn128Field::from(2)),
+            ]
+            .into_iter()
+            .collect();
+
+            assert_eq!(
+                program().public_inputs_from_map(&named),
+                Err(PublicInputsError::Missing("_1".to_string()))
+            );
+        }
+
+        #[test]
+        fn unexpected() {
+            let named = vec![
+                ("~out_0".to_string(), Bn128Field::from(5)),
+                ("_1".to_string(), Bn128Field::from(3)),
+                ("_0".to_string(), Bn128Field::from(2)),
+                ("_2".to_string(), Bn128Field::from(2)),
+            ]
+            .into_iter()
+            .collect();
+
+            assert_eq!(
+                program().public_inputs_from_map(&named),
+                Err(PublicInputsError::Unexpected("_2".to_string()))
+            );
+        }
+    }
 }
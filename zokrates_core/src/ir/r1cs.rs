@@ -0,0 +1,494 @@
+//! Export of the constraint system and witness in the iden3 `.r1cs` and `.wtns` binary formats
+//! used by the circom/snarkjs ecosystem.
+//!
+//! Wires are numbered as follows:
+//! * wire 0 is `~one`
+//! * then the return values, in order
+//! * then the public arguments, in order
+//! * then the private arguments, in order
+//! * then all other variables, in order of first appearance
+//!
+//! All integers are little-endian, field elements are written as `n8`-byte little-endian
+//! integers where `n8` is the size of the modulus rounded up to a multiple of 8 bytes.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{CanonicalLinComb, Prog, Statement, Witness};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Read, Write};
+use zokrates_field::Field;
+
+const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+const R1CS_VERSION: u32 = 1;
+const R1CS_HEADER_SECTION: u32 = 1;
+const R1CS_CONSTRAINT_SECTION: u32 = 2;
+const R1CS_WIRE2LABEL_SECTION: u32 = 3;
+
+const WTNS_MAGIC: &[u8; 4] = b"wtns";
+const WTNS_VERSION: u32 = 2;
+const WTNS_HEADER_SECTION: u32 = 1;
+const WTNS_DATA_SECTION: u32 = 2;
+
+/// The number of bytes used to encode a field element
+fn field_size<T: Field>() -> usize {
+    (T::get_required_bits() + 63) / 64 * 8
+}
+
+fn write_field<T: Field, W: Write>(w: &mut W, e: &T) -> io::Result<()> {
+    let mut bytes = e.into_byte_vector();
+    bytes.resize(field_size::<T>(), 0);
+    w.write_all(&bytes)
+}
+
+fn write_modulus<T: Field, W: Write>(w: &mut W) -> io::Result<()> {
+    let mut bytes = (T::max_value().to_biguint() + 1u32).to_bytes_le();
+    bytes.resize(field_size::<T>(), 0);
+    w.write_all(&bytes)
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn invalid_data<S: Into<String>>(message: S) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn write_section<W: Write>(w: &mut W, section_type: u32, content: &[u8]) -> io::Result<()> {
+    w.write_all(&section_type.to_le_bytes())?;
+    w.write_all(&(content.len() as u64).to_le_bytes())?;
+    w.write_all(content)
+}
+
+/// A matrix in coordinate form, listing the row, column and value of each non-zero entry
+#[derive(Debug, PartialEq, Clone)]
+pub struct SparseMatrix<T> {
+    pub rows: usize,
+    pub columns: usize,
+    pub entries: Vec<(usize, usize, T)>,
+}
+
+impl<T: Field> Prog<T> {
+    /// Returns the variables of this program in wire order
+    pub fn wires(&self) -> Vec<FlatVariable> {
+        let mut wires = vec![FlatVariable::one()];
+        wires.extend(self.main.returns.iter().cloned());
+        wires.extend(
+            self.parameters()
+                .iter()
+                .filter(|p| !p.private)
+                .map(|p| p.id),
+        );
+        wires.extend(self.parameters().iter().filter(|p| p.private).map(|p| p.id));
+
+        let mut seen: HashSet<FlatVariable> = wires.iter().cloned().collect();
+
+        for statement in &self.main.statements {
+            let variables: Vec<FlatVariable> = match statement {
+                Statement::Constraint(quad, lin) => quad
+                    .left
+                    .0
+                    .iter()
+                    .chain(quad.right.0.iter())
+                    .chain(lin.0.iter())
+                    .map(|(v, _)| *v)
+                    .collect(),
+                Statement::Directive(d) => d.outputs.clone(),
+            };
+
+            for v in variables {
+                if seen.insert(v) {
+                    wires.push(v);
+                }
+            }
+        }
+
+        wires
+    }
+
+    /// Returns the `A`, `B` and `C` matrices of the constraint system, such that each constraint `a * b == c`
+    /// corresponds to a row. Columns are wires, in the order of `wires`. Entries of a row are sorted by column.
+    pub fn to_sparse_matrices(&self) -> (SparseMatrix<T>, SparseMatrix<T>, SparseMatrix<T>) {
+        let wires = self.wires();
+        let indices: BTreeMap<FlatVariable, usize> =
+            wires.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+
+        let matrix = || SparseMatrix {
+            rows: self.constraint_count(),
+            columns: wires.len(),
+            entries: vec![],
+        };
+        let (mut a, mut b, mut c) = (matrix(), matrix(), matrix());
+
+        let push = |m: &mut SparseMatrix<T>, row: usize, l: CanonicalLinComb<T>| {
+            let mut entries: Vec<_> =
+                l.0.into_iter()
+                    .map(|(v, e)| (row, indices[&v], e))
+                    .collect();
+            entries.sort_by_key(|(_, column, _)| *column);
+            m.entries.extend(entries);
+        };
+
+        for (row, (quad, lin)) in self.constraints().enumerate() {
+            push(&mut a, row, quad.left.clone().into_canonical());
+            push(&mut b, row, quad.right.clone().into_canonical());
+            push(&mut c, row, lin.clone().into_canonical());
+        }
+
+        (a, b, c)
+    }
+
+    /// Writes the constraint system in the `.r1cs` binary format
+    pub fn write_r1cs_binary<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let wires = self.wires();
+        let indices: BTreeMap<FlatVariable, u32> = wires
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (*v, i as u32))
+            .collect();
+
+        let public_count = self.private.iter().filter(|p| !**p).count();
+
+        let mut header = vec![];
+        header.write_all(&(field_size::<T>() as u32).to_le_bytes())?;
+        write_modulus::<T, _>(&mut header)?;
+        header.write_all(&(wires.len() as u32).to_le_bytes())?;
+        header.write_all(&(self.main.returns.len() as u32).to_le_bytes())?;
+        header.write_all(&(public_count as u32).to_le_bytes())?;
+        header.write_all(&((self.private.len() - public_count) as u32).to_le_bytes())?;
+        header.write_all(&(wires.len() as u64).to_le_bytes())?;
+        header.write_all(&(self.constraint_count() as u32).to_le_bytes())?;
+
+        let write_lincomb = |buf: &mut Vec<u8>, l: CanonicalLinComb<T>| -> io::Result<()> {
+            let mut terms: Vec<_> = l.0.into_iter().map(|(v, c)| (indices[&v], c)).collect();
+            terms.sort_by_key(|(i, _)| *i);
+            buf.write_all(&(terms.len() as u32).to_le_bytes())?;
+            for (i, c) in terms {
+                buf.write_all(&i.to_le_bytes())?;
+                write_field(buf, &c)?;
+            }
+            Ok(())
+        };
+
+        let mut constraints = vec![];
+        for (quad, lin) in self.constraints() {
+            write_lincomb(&mut constraints, quad.left.clone().into_canonical())?;
+            write_lincomb(&mut constraints, quad.right.clone().into_canonical())?;
+            write_lincomb(&mut constraints, lin.clone().into_canonical())?;
+        }
+
+        let mut labels = vec![];
+        for i in 0..wires.len() {
+            labels.write_all(&(i as u64).to_le_bytes())?;
+        }
+
+        w.write_all(R1CS_MAGIC)?;
+        w.write_all(&R1CS_VERSION.to_le_bytes())?;
+        w.write_all(&3u32.to_le_bytes())?;
+        write_section(&mut w, R1CS_HEADER_SECTION, &header)?;
+        write_section(&mut w, R1CS_CONSTRAINT_SECTION, &constraints)?;
+        write_section(&mut w, R1CS_WIRE2LABEL_SECTION, &labels)?;
+
+        Ok(())
+    }
+}
+
+impl<T: Field> Witness<T> {
+    /// Writes the witness in the `.wtns` binary format, using the wire order of `program`
+    pub fn write_wtns_binary<W: Write>(&self, program: &Prog<T>, mut w: W) -> io::Result<()> {
+        let wires = program.wires();
+
+        let mut header = vec![];
+        header.write_all(&(field_size::<T>() as u32).to_le_bytes())?;
+        write_modulus::<T, _>(&mut header)?;
+        header.write_all(&(wires.len() as u32).to_le_bytes())?;
+
+        let mut values = vec![];
+        for wire in &wires {
+            let value = self.0.get(wire).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Witness is missing a value for {}", wire),
+                )
+            })?;
+            write_field(&mut values, value)?;
+        }
+
+        w.write_all(WTNS_MAGIC)?;
+        w.write_all(&WTNS_VERSION.to_le_bytes())?;
+        w.write_all(&2u32.to_le_bytes())?;
+        write_section(&mut w, WTNS_HEADER_SECTION, &header)?;
+        write_section(&mut w, WTNS_DATA_SECTION, &values)?;
+
+        Ok(())
+    }
+
+    /// Reads a witness in the `.wtns` binary format, mapping wires back to variables using the wire order of `program`
+    pub fn read_wtns_binary<R: Read>(program: &Prog<T>, mut r: R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != WTNS_MAGIC {
+            return Err(invalid_data("Wrong magic number"));
+        }
+
+        if read_u32(&mut r)? != WTNS_VERSION {
+            return Err(invalid_data("Unknown version"));
+        }
+
+        let wires = program.wires();
+        let n8 = field_size::<T>();
+
+        let mut modulus = vec![];
+        write_modulus::<T, _>(&mut modulus)?;
+
+        let mut header_found = false;
+        let mut values = None;
+
+        for _ in 0..read_u32(&mut r)? {
+            let section_type = read_u32(&mut r)?;
+            let section_size = read_u64(&mut r)?;
+            // the size is untrusted, so the buffer only grows with the bytes actually read
+            let mut content = vec![];
+            (&mut r).take(section_size).read_to_end(&mut content)?;
+            if content.len() as u64 != section_size {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Section is shorter than its size",
+                ));
+            }
+            let mut content = content.as_slice();
+
+            match section_type {
+                WTNS_HEADER_SECTION => {
+                    if read_u32(&mut content)? as usize != n8 {
+                        return Err(invalid_data(format!(
+                            "Field size does not match {}",
+                            T::name()
+                        )));
+                    }
+                    let mut prime = vec![0u8; n8];
+                    content.read_exact(&mut prime)?;
+                    if prime != modulus {
+                        return Err(invalid_data(format!(
+                            "Field modulus does not match {}",
+                            T::name()
+                        )));
+                    }
+                    let count = read_u32(&mut content)? as usize;
+                    if count != wires.len() {
+                        return Err(invalid_data(format!(
+                            "Expected {} values, found {}",
+                            wires.len(),
+                            count
+                        )));
+                    }
+                    header_found = true;
+                }
+                WTNS_DATA_SECTION => {
+                    if !header_found {
+                        return Err(invalid_data("Data section found before header section"));
+                    }
+                    if content.len() != wires.len() * n8 {
+                        return Err(invalid_data("Unexpected data section size"));
+                    }
+                    values = Some(
+                        content
+                            .chunks(n8)
+                            .map(|chunk| {
+                                let value = T::from_byte_vector(chunk.to_vec());
+                                match value.to_biguint() <= T::max_value().to_biguint() {
+                                    true => Ok(value),
+                                    false => Err(invalid_data("Value is not a field element")),
+                                }
+                            })
+                            .collect::<io::Result<Vec<T>>>()?,
+                    );
+                }
+                // ignore unknown sections
+                _ => {}
+            }
+        }
+
+        let values = values.ok_or_else(|| invalid_data("Missing data section"))?;
+
+        Ok(Witness(wires.into_iter().zip(values).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Interpreter;
+    use crate::testing::{identity, increment};
+    use std::convert::TryInto;
+    use zokrates_field::Bn128Field;
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn r1cs_header() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let mut buf = vec![];
+        program.write_r1cs_binary(&mut buf).unwrap();
+
+        assert_eq!(&buf[0..4], b"r1cs");
+        assert_eq!(u32_at(&buf, 4), 1);
+        // three sections: header, constraints, wire to label map
+        assert_eq!(u32_at(&buf, 8), 3);
+
+        // header section
+        assert_eq!(u32_at(&buf, 12), 1);
+        let header_size = u64_at(&buf, 16) as usize;
+        let header = &buf[24..24 + header_size];
+        assert_eq!(u32_at(header, 0), 32);
+        let counts = &header[4 + 32..];
+        // wires: ~one, ~out_0, _0
+        assert_eq!(u32_at(counts, 0), 3);
+        // public outputs
+        assert_eq!(u32_at(counts, 4), 1);
+        // public inputs
+        assert_eq!(u32_at(counts, 8), 1);
+        // private inputs
+        assert_eq!(u32_at(counts, 12), 0);
+        // labels
+        assert_eq!(u64_at(counts, 16), 3);
+        // constraints
+        assert_eq!(u32_at(counts, 24), 1);
+
+        // constraint section follows
+        let offset = 24 + header_size;
+        assert_eq!(u32_at(&buf, offset), 2);
+        // each of the three lincombs of the single constraint has one term
+        let constraint_size = u64_at(&buf, offset + 4) as usize;
+        assert_eq!(constraint_size, 3 * (4 + 4 + 32));
+
+        // wire to label section comes last
+        let offset = offset + 12 + constraint_size;
+        assert_eq!(u32_at(&buf, offset), 3);
+        assert_eq!(u64_at(&buf, offset + 4), 3 * 8);
+        assert_eq!(buf.len(), offset + 12 + 3 * 8);
+    }
+
+    #[test]
+    fn wtns_header() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+
+        let mut buf = vec![];
+        witness.write_wtns_binary(&program, &mut buf).unwrap();
+
+        assert_eq!(&buf[0..4], b"wtns");
+        assert_eq!(u32_at(&buf, 4), 2);
+        assert_eq!(u32_at(&buf, 8), 2);
+
+        assert_eq!(u32_at(&buf, 12), 1);
+        let header_size = u64_at(&buf, 16) as usize;
+        assert_eq!(header_size, 4 + 32 + 4);
+        assert_eq!(u32_at(&buf, 24 + 4 + 32), 3);
+
+        let offset = 24 + header_size;
+        assert_eq!(u32_at(&buf, offset), 2);
+        assert_eq!(u64_at(&buf, offset + 4), 3 * 32);
+
+        // values are in wire order: ~one, ~out_0, _0
+        let values = &buf[offset + 12..];
+        assert_eq!(values[0], 1);
+        assert_eq!(values[32], 42);
+        assert_eq!(values[64], 42);
+    }
+
+    #[test]
+    fn wtns_round_trip() {
+        use crate::testing::assert_witness_verifies;
+
+        let program: Prog<Bn128Field> = increment();
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(3)])
+            .unwrap();
+
+        let mut buf = vec![];
+        witness.write_wtns_binary(&program, &mut buf).unwrap();
+
+        let read = Witness::read_wtns_binary(&program, buf.as_slice()).unwrap();
+        assert_eq!(read, witness);
+
+        assert_witness_verifies(program, read);
+    }
+
+    #[test]
+    fn wtns_wrong_field() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+
+        let mut buf = vec![];
+        witness.write_wtns_binary(&program, &mut buf).unwrap();
+
+        // corrupt the modulus in the header
+        buf[28] ^= 1;
+
+        assert!(Witness::read_wtns_binary(&program, buf.as_slice()).is_err());
+    }
+
+    #[test]
+    fn wtns_huge_section() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+
+        let mut buf = vec![];
+        witness.write_wtns_binary(&program, &mut buf).unwrap();
+
+        // claim that the header section spans almost 2^64 bytes
+        buf[16..24].copy_from_slice(&(std::u64::MAX - 1).to_le_bytes());
+
+        assert_eq!(
+            Witness::read_wtns_binary(&program, buf.as_slice())
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn sparse_matrices() {
+        let program: Prog<Bn128Field> = increment();
+
+        let (a, b, c) = program.to_sparse_matrices();
+
+        // one constraint over ~one, ~out_0 and _42
+        for m in &[&a, &b, &c] {
+            assert_eq!((m.rows, m.columns), (1, 3));
+        }
+
+        // a linear combination is multiplied by `~one` on the left
+        assert_eq!(a.entries, vec![(0, 0, Bn128Field::from(1))]);
+        assert_eq!(
+            b.entries,
+            vec![(0, 0, Bn128Field::from(1)), (0, 2, Bn128Field::from(1))]
+        );
+        assert_eq!(c.entries, vec![(0, 1, Bn128Field::from(1))]);
+    }
+}
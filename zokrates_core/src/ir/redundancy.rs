@@ -0,0 +1,103 @@
+//! Detection of linear constraints implied by other linear constraints
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{CanonicalLinComb, LinComb, Prog};
+use std::collections::BTreeMap;
+use zokrates_field::Field;
+
+impl<T: Field> Prog<T> {
+    /// Returns the indices, among the constraints of this program, of the linear constraints which are linear
+    /// combinations of earlier linear constraints, and thus always hold when those do.
+    ///
+    /// This only takes linear constraints into account: a linear constraint only implied with the help of a
+    /// quadratic one is not detected, so an empty result does not mean that no constraint is redundant. Removing a
+    /// reported constraint keeps the set of valid witnesses unchanged, but the interpreter may rely on it to compute
+    /// a variable.
+    pub fn find_redundant_constraints(&self) -> Vec<usize> {
+        // rows in echelon form, keyed by their smallest variable, whose coefficient is one
+        let mut basis: BTreeMap<FlatVariable, CanonicalLinComb<T>> = BTreeMap::new();
+
+        self.constraints()
+            .enumerate()
+            .filter_map(|(index, (quad, lin))| {
+                let mut row = (quad.try_linear()? - lin.clone()).into_canonical();
+
+                // eliminate the smallest variable of the row while it is the pivot of a basis row. Basis rows only
+                // have larger variables than their pivot, so this terminates.
+                while let Some((pivot, coefficient)) = row
+                    .0
+                    .iter()
+                    .next()
+                    .map(|(v, c)| (*v, c.clone()))
+                    .filter(|(v, _)| basis.contains_key(v))
+                {
+                    row = (LinComb::from(row)
+                        - LinComb::from(basis[&pivot].clone()) * &coefficient)
+                        .into_canonical();
+                }
+
+                match row.0.iter().next().map(|(v, c)| (*v, c.clone())) {
+                    None => Some(index),
+                    Some((pivot, coefficient)) => {
+                        basis.insert(pivot, (LinComb::from(row) / &coefficient).into_canonical());
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{ProgBuilder, QuadComb};
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn duplicate() {
+        let mut builder = ProgBuilder::<Bn128Field>::new();
+        let a = builder.private_input();
+        let b = builder.private_input();
+        let c = builder.variable();
+        // 0: c == a + b
+        builder.constrain(LinComb::from(a) + LinComb::from(b), c);
+        // 1: d == a * b, which is quadratic
+        let d = builder.variable();
+        builder.constrain(QuadComb::from_linear_combinations(a.into(), b.into()), d);
+        // 2: the same as 0
+        builder.assert_eq(LinComb::from(a) + LinComb::from(b), c);
+        // 3: 0 scaled by 2
+        builder.assert_eq(
+            LinComb::summand(2, a) + LinComb::summand(2, b),
+            LinComb::summand(2, c),
+        );
+        // 4: e == c + a
+        let e = builder.variable();
+        builder.constrain(LinComb::from(c) + LinComb::from(a), e);
+        // 5: follows from 0 and 4
+        builder.assert_eq(e, LinComb::summand(2, a) + LinComb::from(b));
+        // 6: not implied
+        builder.assert_eq(e, d);
+        // 7: ~out_0 == e
+        builder.output(e);
+        let program = builder.build().unwrap();
+
+        assert_eq!(program.find_redundant_constraints(), vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn implied_by_constants() {
+        let mut builder = ProgBuilder::<Bn128Field>::new();
+        let a = builder.private_input();
+        let b = builder.private_input();
+        builder.assert_eq(a, LinComb::one());
+        builder.assert_eq(b, LinComb::one());
+        builder.assert_eq(a, b);
+        builder.output(a);
+        let program = builder.build().unwrap();
+
+        // `a == b` follows from the first two constraints
+        assert_eq!(program.find_redundant_constraints(), vec![2]);
+    }
+}
@@ -0,0 +1,141 @@
+//! Source locations of the statements of a program, kept on the side so that they do not affect proving
+
+use crate::ir::{Prog, Witness};
+use std::collections::BTreeMap;
+use std::fmt;
+use zokrates_field::Field;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SourceSpan {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.col)
+    }
+}
+
+/// The source spans of the statements of a program, keyed by statement index. Statements without a span,
+/// such as those generated by the compiler, are simply absent.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SourceMap(BTreeMap<usize, SourceSpan>);
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attaches `span` to the statement at index `statement`
+    pub fn insert(&mut self, statement: usize, span: SourceSpan) {
+        self.0.insert(statement, span);
+    }
+
+    pub fn get(&self, statement: usize) -> Option<SourceSpan> {
+        self.0.get(&statement).cloned()
+    }
+}
+
+/// A constraint not satisfied by a witness, along with its source span if known
+#[derive(Debug, PartialEq)]
+pub struct ConstraintFailure {
+    pub statement: usize,
+    pub span: Option<SourceSpan>,
+}
+
+impl fmt::Display for ConstraintFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.span {
+            Some(ref span) => write!(
+                f,
+                "Constraint {} at {} is not satisfied",
+                self.statement, span
+            ),
+            None => write!(f, "Constraint {} is not satisfied", self.statement),
+        }
+    }
+}
+
+impl<T: Field> Prog<T> {
+    /// Same as `check_constraints`, reporting the source span of each unsatisfied constraint found in `spans`
+    pub fn check_constraints_with_spans(
+        &self,
+        witness: &Witness<T>,
+        spans: &SourceMap,
+    ) -> Result<(), Vec<ConstraintFailure>> {
+        self.check_constraints(witness).map_err(|unsatisfied| {
+            unsatisfied
+                .into_iter()
+                .map(|statement| ConstraintFailure {
+                    statement,
+                    span: spans.get(statement),
+                })
+                .collect()
+        })
+    }
+
+    /// Prints this program like its `Display` implementation, annotating each statement with its source span
+    pub fn pretty_print(&self, spans: &SourceMap) -> String {
+        let mut res = String::new();
+        self.main
+            .write_annotated(&mut res, |index| spans.get(index))
+            .unwrap();
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::FlatVariable;
+    use crate::ir::ProgBuilder;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn span_in_failure() {
+        let mut builder = ProgBuilder::<Bn128Field>::new();
+        let a = builder.private_input();
+        let b = builder.private_input();
+        builder.assert_eq(a, b);
+        builder.output(a);
+        let program = builder.build().unwrap();
+
+        let mut spans = SourceMap::new();
+        spans.insert(0, SourceSpan { line: 12, col: 5 });
+
+        let witness = Witness(
+            vec![
+                (FlatVariable::one(), Bn128Field::from(1)),
+                (a, Bn128Field::from(1)),
+                (b, Bn128Field::from(2)),
+                (FlatVariable::public(0), Bn128Field::from(1)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let failures = program
+            .check_constraints_with_spans(&witness, &spans)
+            .unwrap_err();
+        assert_eq!(
+            failures,
+            vec![ConstraintFailure {
+                statement: 0,
+                span: Some(SourceSpan { line: 12, col: 5 })
+            }]
+        );
+        assert_eq!(
+            failures[0].to_string(),
+            "Constraint 0 at line 12, column 5 is not satisfied"
+        );
+
+        assert_eq!(program.pretty_print(&SourceMap::new()), program.to_string());
+        assert!(program
+            .pretty_print(&spans)
+            .lines()
+            .nth(1)
+            .unwrap()
+            .ends_with("// line 12, column 5"));
+    }
+}
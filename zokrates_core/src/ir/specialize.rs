@@ -0,0 +1,184 @@
+//! Specialization of programs, where some public inputs are fixed to constants
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Directive, Function, LinComb, Prog, QuadComb, Statement};
+use std::collections::BTreeMap;
+use zokrates_field::Field;
+
+impl<T: Field> Prog<T> {
+    /// Replaces the public arguments and return values in `fixed` with the given constants, and removes them
+    /// from the inputs of the program. The remaining return values are renumbered from `~out_0`, so the resulting
+    /// program has fewer public inputs and a smaller verification key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable in `fixed` is neither a public argument nor a return value of this program.
+    pub fn specialize(self, fixed: &BTreeMap<FlatVariable, T>) -> Prog<T> {
+        let public_arguments: Vec<FlatVariable> = self
+            .input_signature()
+            .into_iter()
+            .filter(|(_, private)| !private)
+            .map(|(v, _)| v)
+            .collect();
+
+        for v in fixed.keys() {
+            assert!(
+                public_arguments.contains(v) || self.main.returns.contains(v),
+                "Only public inputs can be specialized, found {}",
+                v
+            );
+        }
+
+        let (arguments, private): (Vec<_>, Vec<_>) = self
+            .input_signature()
+            .into_iter()
+            .filter(|(v, _)| !fixed.contains_key(v))
+            .unzip();
+
+        let returns: Vec<FlatVariable> = self
+            .main
+            .returns
+            .iter()
+            .filter(|v| !fixed.contains_key(v))
+            .cloned()
+            .collect();
+
+        let renamed: BTreeMap<FlatVariable, FlatVariable> = returns
+            .iter()
+            .enumerate()
+            .map(|(index, v)| (*v, FlatVariable::public(index)))
+            .collect();
+
+        let variable = |v: FlatVariable| renamed.get(&v).cloned().unwrap_or(v);
+
+        let lin = |l: LinComb<T>| {
+            LinComb(
+                l.0.into_iter()
+                    .map(|(v, coeff)| match fixed.get(&v) {
+                        Some(value) => (FlatVariable::one(), coeff * value),
+                        None => (variable(v), coeff),
+                    })
+                    .collect(),
+            )
+        };
+
+        let quad = |q: QuadComb<T>| QuadComb::from_linear_combinations(lin(q.left), lin(q.right));
+
+        let statements = self
+            .main
+            .statements
+            .into_iter()
+            .map(|s| match s {
+                Statement::Constraint(q, l) => Statement::Constraint(quad(q), lin(l)),
+                Statement::Directive(d) => Statement::Directive(Directive {
+                    inputs: d.inputs.into_iter().map(quad).collect(),
+                    outputs: d.outputs.into_iter().map(variable).collect(),
+                    solver: d.solver,
+                }),
+            })
+            .collect();
+
+        Prog {
+            main: Function {
+                id: self.main.id,
+                statements,
+                arguments,
+                returns: returns.into_iter().map(variable).collect(),
+            },
+            private,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Interpreter;
+    use crate::proof_system::bellman::Computation;
+    use crate::testing::assert_witness_verifies;
+    use zokrates_field::Bn128Field;
+
+    fn program() -> Prog<Bn128Field> {
+        Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(42), FlatVariable::new(51)],
+                returns: vec![FlatVariable::public(0), FlatVariable::public(1)],
+                statements: vec![
+                    Statement::Constraint(
+                        (LinComb::from(FlatVariable::new(42))
+                            + LinComb::from(FlatVariable::new(51)))
+                        .into(),
+                        FlatVariable::public(0).into(),
+                    ),
+                    Statement::Constraint(
+                        (LinComb::from(FlatVariable::one()) + LinComb::from(FlatVariable::new(42)))
+                            .into(),
+                        FlatVariable::public(1).into(),
+                    ),
+                ],
+            },
+            private: vec![true, false],
+        }
+    }
+
+    #[test]
+    fn public_argument() {
+        let fixed = vec![(FlatVariable::new(51), Bn128Field::from(4))]
+            .into_iter()
+            .collect();
+        let specialized = program().specialize(&fixed);
+
+        assert_eq!(specialized.main.arguments, vec![FlatVariable::new(42)]);
+        assert_eq!(specialized.private, vec![true]);
+
+        let witness = Interpreter::default()
+            .execute(&specialized, &vec![Bn128Field::from(3)])
+            .unwrap();
+        assert_eq!(
+            witness.return_values(),
+            vec![Bn128Field::from(7), Bn128Field::from(4)]
+        );
+
+        let computation = Computation::with_witness(specialized.clone(), witness.clone());
+        assert_eq!(computation.public_inputs_values().len(), 2);
+
+        assert_witness_verifies(specialized, witness);
+    }
+
+    #[test]
+    fn return_value() {
+        let fixed = vec![(FlatVariable::public(0), Bn128Field::from(7))]
+            .into_iter()
+            .collect();
+        let specialized = program().specialize(&fixed);
+
+        assert_eq!(specialized.main.returns, vec![FlatVariable::public(0)]);
+        assert_eq!(specialized.validate(), Ok(()));
+
+        let witness = Interpreter::default()
+            .execute(
+                &specialized,
+                &vec![Bn128Field::from(3), Bn128Field::from(4)],
+            )
+            .unwrap();
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(4)]);
+
+        // 3 + 5 != 7
+        assert!(Interpreter::default()
+            .execute(
+                &specialized,
+                &vec![Bn128Field::from(3), Bn128Field::from(5)]
+            )
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only public inputs can be specialized, found _42")]
+    fn private_argument() {
+        let fixed = vec![(FlatVariable::new(42), Bn128Field::from(3))]
+            .into_iter()
+            .collect();
+        program().specialize(&fixed);
+    }
+}
@@ -0,0 +1,114 @@
+//! Truncation of programs to a prefix of their constraints, to bisect failures in large programs
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Directive, Function, LinComb, Prog, QuadComb, Statement};
+use std::collections::{BTreeMap, BTreeSet};
+use zokrates_field::Field;
+
+impl<T: Field> Prog<T> {
+    /// Returns the program made of the statements of this program up to its `up_to`-th constraint, dropping the
+    /// directives which come after it. Only the return values which the kept statements still refer to are
+    /// returned, renumbered from `~out_0`, so that the result can be set up and proven like any other program.
+    pub fn truncate(&self, up_to: usize) -> Prog<T> {
+        let mut constraints = 0;
+        let statements: Vec<Statement<T>> = self
+            .main
+            .statements
+            .iter()
+            .take_while(|s| match s {
+                Statement::Constraint(..) => {
+                    constraints += 1;
+                    constraints <= up_to
+                }
+                Statement::Directive(_) => constraints < up_to,
+            })
+            .cloned()
+            .collect();
+
+        let defined: BTreeSet<FlatVariable> =
+            statements.iter().flat_map(|s| s.variables()).collect();
+
+        let returns: Vec<FlatVariable> = self
+            .main
+            .returns
+            .iter()
+            .filter(|v| defined.contains(v))
+            .cloned()
+            .collect();
+
+        let renamed: BTreeMap<FlatVariable, FlatVariable> = returns
+            .iter()
+            .enumerate()
+            .map(|(index, v)| (*v, FlatVariable::public(index)))
+            .collect();
+
+        let variable = |v: FlatVariable| renamed.get(&v).cloned().unwrap_or(v);
+        let lin = |l: LinComb<T>| LinComb(l.0.into_iter().map(|(v, c)| (variable(v), c)).collect());
+        let quad = |q: QuadComb<T>| QuadComb::from_linear_combinations(lin(q.left), lin(q.right));
+
+        Prog {
+            main: Function {
+                id: self.main.id.clone(),
+                arguments: self.main.arguments.clone(),
+                returns: returns.into_iter().map(variable).collect(),
+                statements: statements
+                    .into_iter()
+                    .map(|s| match s {
+                        Statement::Constraint(q, l) => Statement::Constraint(quad(q), lin(l)),
+                        Statement::Directive(d) => Statement::Directive(Directive {
+                            inputs: d.inputs.into_iter().map(quad).collect(),
+                            outputs: d.outputs.into_iter().map(variable).collect(),
+                            solver: d.solver,
+                        }),
+                    })
+                    .collect(),
+            },
+            private: self.private.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Interpreter;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn renumber_returns() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0), FlatVariable::public(1)],
+                statements: vec![
+                    Statement::Constraint(
+                        (LinComb::from(FlatVariable::new(0)) + LinComb::one()).into(),
+                        FlatVariable::public(1).into(),
+                    ),
+                    Statement::Constraint(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::public(0).into(),
+                    ),
+                ],
+            },
+            private: vec![true],
+        };
+
+        assert_eq!(program.truncate(2), program);
+
+        let truncated = program.truncate(1);
+        assert_eq!(truncated.main.returns, vec![FlatVariable::public(0)]);
+        assert_eq!(truncated.validate(), Ok(()));
+        assert_eq!(
+            Interpreter::default()
+                .execute(&truncated, &vec![Bn128Field::from(41)])
+                .unwrap()
+                .return_values(),
+            vec![Bn128Field::from(42)]
+        );
+
+        assert_eq!(program.truncate(0).main.statements, vec![]);
+        assert_eq!(program.truncate(0).main.returns, vec![]);
+    }
+}
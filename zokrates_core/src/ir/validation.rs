@@ -0,0 +1,201 @@
+//! Structural checks on programs, meant to catch malformed programs before they are used
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{LinComb, Prog, QuadComb, Statement};
+use std::collections::BTreeSet;
+use std::fmt;
+use zokrates_field::Field;
+
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    /// A return variable never appears on the output side of a constraint
+    UnconstrainedReturn(FlatVariable),
+    /// The return variables are not `~out_0`, `~out_1`... in this order
+    UnorderedReturns,
+    /// A statement uses a variable which is neither an argument nor defined by a previous statement
+    UndefinedVariable {
+        variable: FlatVariable,
+        statement: usize,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValidationError::UnconstrainedReturn(ref v) => {
+                write!(f, "Return variable {} is not constrained", v)
+            }
+            ValidationError::UnorderedReturns => {
+                write!(f, "Return variables must be ~out_0, ~out_1... in order")
+            }
+            ValidationError::UndefinedVariable {
+                ref variable,
+                statement,
+            } => write!(
+                f,
+                "Variable {} is used in statement {} before being defined",
+                variable, statement
+            ),
+        }
+    }
+}
+
+impl<T: Field> Prog<T> {
+    /// Runs every structural check on this program, reporting all the problems found
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+
+        if let Err(returns) = self.validate_returns() {
+            errors.extend(
+                returns
+                    .into_iter()
+                    .map(ValidationError::UnconstrainedReturn),
+            );
+        }
+
+        if self
+            .main
+            .returns
+            .iter()
+            .enumerate()
+            .any(|(index, v)| *v != FlatVariable::public(index))
+        {
+            errors.push(ValidationError::UnorderedReturns);
+        }
+
+        errors.extend(self.undefined_variables());
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    /// Checks that every return variable appears on the output side of a constraint, failing with the
+    /// ones which do not
+    pub fn validate_returns(&self) -> Result<(), Vec<FlatVariable>> {
+        let constrained: BTreeSet<FlatVariable> = self
+            .constraints()
+            .flat_map(|(_, lin)| lin.0.iter().map(|(v, _)| *v))
+            .collect();
+
+        let unconstrained: Vec<FlatVariable> = self
+            .main
+            .returns
+            .iter()
+            .filter(|v| !constrained.contains(v))
+            .cloned()
+            .collect();
+
+        match unconstrained.is_empty() {
+            true => Ok(()),
+            false => Err(unconstrained),
+        }
+    }
+
+    /// Checks that each statement only uses variables which are known when it is executed: the arguments,
+    /// `~one` and the variables defined by previous statements. A constraint whose output side is a single
+    /// unknown variable with coefficient one defines it, as in the interpreter.
+    fn undefined_variables(&self) -> Vec<ValidationError> {
+        fn variables<T: Field>(lin: &LinComb<T>) -> impl Iterator<Item = FlatVariable> + '_ {
+            lin.0.iter().map(|(v, _)| *v)
+        }
+
+        fn quad_variables<T: Field>(quad: &QuadComb<T>) -> impl Iterator<Item = FlatVariable> + '_ {
+            variables(&quad.left).chain(variables(&quad.right))
+        }
+
+        let mut known: BTreeSet<FlatVariable> = self.main.arguments.iter().cloned().collect();
+        known.insert(FlatVariable::one());
+
+        let mut errors = vec![];
+
+        for (index, statement) in self.main.statements.iter().enumerate() {
+            let (used, defined): (Vec<_>, Vec<_>) = match statement {
+                Statement::Constraint(quad, lin) => match lin.0.as_slice() {
+                    [(v, c)] if *c == T::one() && !known.contains(v) => {
+                        (quad_variables(quad).collect(), vec![*v])
+                    }
+                    _ => (quad_variables(quad).chain(variables(lin)).collect(), vec![]),
+                },
+                Statement::Directive(d) => (
+                    d.inputs.iter().flat_map(quad_variables).collect(),
+                    d.outputs.clone(),
+                ),
+            };
+
+            let undefined: BTreeSet<_> = used.into_iter().filter(|v| !known.contains(v)).collect();
+            errors.extend(undefined.into_iter().map(|variable| {
+                ValidationError::UndefinedVariable {
+                    variable,
+                    statement: index,
+                }
+            }));
+
+            known.extend(defined);
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Function, Statement};
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn several_problems() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(1)],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(42).into(),
+                    FlatVariable::public(1).into(),
+                )],
+            },
+            private: vec![true],
+        };
+
+        assert_eq!(
+            program.validate(),
+            Err(vec![
+                ValidationError::UnorderedReturns,
+                ValidationError::UndefinedVariable {
+                    variable: FlatVariable::new(42),
+                    statement: 0
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn unconstrained_return() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0), FlatVariable::public(1)],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            private: vec![true],
+        };
+
+        assert_eq!(
+            program.validate_returns(),
+            Err(vec![FlatVariable::public(1)])
+        );
+        assert_eq!(
+            program.validate(),
+            Err(vec![ValidationError::UnconstrainedReturn(
+                FlatVariable::public(1)
+            )])
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use crate::flat_absy::FlatVariable;
+use crate::ir::Prog;
+use crate::proof_system::bellman::PublicInputsError;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::io;
@@ -8,6 +10,17 @@ use zokrates_field::Field;
 #[derive(Clone, Debug, PartialEq)]
 pub struct Witness<T>(pub BTreeMap<FlatVariable, T>);
 
+/// A destination for the values of the variables of a program, received one by one as they are computed
+pub trait WitnessSink<T> {
+    fn insert(&mut self, variable: FlatVariable, value: T);
+}
+
+impl<T> WitnessSink<T> for Witness<T> {
+    fn insert(&mut self, variable: FlatVariable, value: T) {
+        self.0.insert(variable, value);
+    }
+}
+
 impl<T: Field> Witness<T> {
     pub fn return_values(&self) -> Vec<T> {
         let out = self
@@ -22,6 +35,42 @@ impl<T: Field> Witness<T> {
             .collect()
     }
 
+    /// Returns the public values of this witness for `program`: its public arguments followed by its
+    /// return values, failing if some of them are missing from the witness or if `~one` is an argument of the
+    /// program
+    ///
+    /// `~one` is always the first public input of the circuit and is implied by the verifier, so it is never
+    /// among the values returned here.
+    pub fn public_view(&self, program: &Prog<T>) -> Result<Vec<T>, PublicInputsError> {
+        if program.main.arguments.contains(&FlatVariable::one()) {
+            return Err(PublicInputsError::OneAsArgument);
+        }
+
+        let public_variables: Vec<&FlatVariable> = program
+            .main
+            .arguments
+            .iter()
+            .zip(program.private.iter())
+            .filter(|(_, private)| !**private)
+            .map(|(a, _)| a)
+            .chain(program.main.returns.iter())
+            .collect();
+
+        let values: Vec<T> = public_variables
+            .iter()
+            .filter_map(|v| self.0.get(v))
+            .cloned()
+            .collect();
+
+        match values.len() == public_variables.len() {
+            true => Ok(values),
+            false => Err(PublicInputsError::LengthMismatch {
+                expected: public_variables.len(),
+                found: values.len(),
+            }),
+        }
+    }
+
     pub fn format_outputs(&self) -> String {
         self.0
             .iter()
@@ -37,6 +86,31 @@ impl<T: Field> Witness<T> {
         Witness(BTreeMap::new())
     }
 
+    /// Returns the variables of this witness with their values. The order only depends on the variables, not on
+    /// how the witness was built: the return values from the last to the first (`~out_1`, `~out_0`), then `~one`,
+    /// then the other variables in increasing order (`_0`, `_1`...). The serializers of this type rely on it.
+    pub fn iter(&self) -> impl Iterator<Item = (&FlatVariable, &T)> {
+        self.0.iter()
+    }
+
+    /// Returns a JSON object mapping each variable to its value as a decimal string. Variables are keyed by
+    /// their name in `names` if any, and by their display name (`_0`, `~one`...) otherwise. Return values
+    /// are always keyed by their display name (`~out_0`, `~out_1`...).
+    pub fn to_json(&self, names: Option<&BTreeMap<FlatVariable, String>>) -> String {
+        let object: serde_json::Map<String, serde_json::Value> = self
+            .iter()
+            .map(|(variable, value)| {
+                let key = match names.and_then(|names| names.get(variable)) {
+                    Some(name) if !variable.is_output() => name.clone(),
+                    _ => variable.to_string(),
+                };
+                (key, serde_json::Value::String(value.to_dec_string()))
+            })
+            .collect();
+
+        serde_json::Value::Object(object).to_string()
+    }
+
     pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
         let mut wtr = csv::WriterBuilder::new()
             .delimiter(b' ')
@@ -45,7 +119,7 @@ impl<T: Field> Witness<T> {
             .from_writer(writer);
 
         // Write each line of the witness to the file
-        for (variable, value) in &self.0 {
+        for (variable, value) in self.iter() {
             wtr.serialize((variable.to_string(), value.to_dec_string()))?;
         }
 
@@ -70,10 +144,10 @@ impl<T: Field> Witness<T> {
                                 format!("Invalid variable in witness: {}", why),
                             )
                         })?;
-                    let value = T::try_from_dec_str(&value).map_err(|_| {
+                    let value = value.parse::<T>().map_err(|why| {
                         io::Error::new(
                             io::ErrorKind::Other,
-                            format!("Invalid value in witness: {}", value),
+                            format!("Invalid value in witness: {} ({})", value, why),
                         )
                     })?;
                     Ok((variable, value))
@@ -87,18 +161,106 @@ impl<T: Field> Witness<T> {
 
         Ok(Witness(map))
     }
+
+    /// Writes this witness in the text format of earlier versions of the CLI: `~one` first, then the intermediate
+    /// variables and finally the return values, each in increasing order
+    pub fn to_zokrates_text(&self) -> String {
+        // variables are ordered by id, outputs having negative ids in decreasing order, followed by `~one` and
+        // the intermediate variables in increasing order
+        let (outputs, others): (Vec<_>, Vec<_>) = self.iter().partition(|(v, _)| v.is_output());
+
+        others
+            .into_iter()
+            .chain(outputs.into_iter().rev())
+            .map(|(v, value)| format!("{} {}\n", v, value.to_dec_string()))
+            .collect()
+    }
+
+    /// Reads a witness in the text format written by earlier versions of the CLI, with one `variable value` line
+    /// per variable. Unlike `read`, blank lines and repeated whitespace are accepted, but a variable may not be
+    /// assigned twice.
+    pub fn from_zokrates_text<R: Read>(mut r: R) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+
+        let mut map = BTreeMap::new();
+
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (variable, value) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [variable, value] => (variable, value),
+                _ => return Err(invalid(format!("Invalid line in witness: {}", line))),
+            };
+
+            let variable = FlatVariable::try_from_human_readable(variable)
+                .map_err(|why| invalid(format!("Invalid variable in witness: {}", why)))?;
+            let value = value
+                .parse::<T>()
+                .map_err(|why| invalid(format!("Invalid value in witness: {} ({})", value, why)))?;
+
+            if map.insert(variable, value).is_some() {
+                return Err(invalid(format!("Variable {} is assigned twice", variable)));
+            }
+        }
+
+        Ok(Witness(map))
+    }
+}
+
+/// The error returned when merging two witnesses which assign different values to the same variable
+#[derive(Debug, PartialEq)]
+pub struct MergeError {
+    pub variable: FlatVariable,
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Variable {} is assigned different values in the merged witnesses",
+            self.variable
+        )
+    }
+}
+
+impl<T: Field> Witness<T> {
+    /// Merges the witness of a sub-circuit into this one. The variables of `other` are renamed using `rename` first,
+    /// and those absent from `rename`, such as `~one`, are kept as is. Fails if a variable ends up with two
+    /// different values.
+    pub fn merge(
+        mut self,
+        other: Witness<T>,
+        rename: &BTreeMap<FlatVariable, FlatVariable>,
+    ) -> Result<Witness<T>, MergeError> {
+        for (variable, value) in other.0 {
+            let variable = rename.get(&variable).cloned().unwrap_or(variable);
+            match self.0.get(&variable) {
+                Some(existing) if *existing != value => return Err(MergeError { variable }),
+                _ => {
+                    self.0.insert(variable, value);
+                }
+            }
+        }
+
+        Ok(self)
+    }
 }
 
+/// Prints one `variable = value` line per variable, in the same order as `write`, followed by the return values
 impl<T: Field> fmt::Display for Witness<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (k, v) in &self.0 {
+            writeln!(f, "{} = {}", k, v.to_dec_string())?;
+        }
         write!(
             f,
-            "{}",
-            self.0
+            "returns: [{}]",
+            self.return_values()
                 .iter()
-                .map(|(k, v)| format!("{} {}", k, v.to_dec_string()))
+                .map(|v| v.to_dec_string())
                 .collect::<Vec<_>>()
-                .join("\n")
+                .join(", ")
         )
     }
 }
@@ -108,8 +270,105 @@ mod tests {
     use super::*;
     use zokrates_field::Bn128Field;
 
+    #[test]
+    fn display() {
+        let w = Witness(
+            vec![
+                (FlatVariable::new(42), Bn128Field::from(3)),
+                (FlatVariable::public(0), Bn128Field::from(4)),
+                (FlatVariable::one(), Bn128Field::from(1)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let display = w.to_string();
+        assert!(display.lines().any(|l| l == "~out_0 = 4"));
+        assert!(display.lines().any(|l| l == "_42 = 3"));
+        assert_eq!(display.lines().count(), 4);
+        assert_eq!(display.lines().last(), Some("returns: [4]"));
+    }
+
+    #[test]
+    fn iter() {
+        let entries = vec![
+            (FlatVariable::new(42), Bn128Field::from(3)),
+            (FlatVariable::public(0), Bn128Field::from(4)),
+            (FlatVariable::new(0), Bn128Field::from(5)),
+            (FlatVariable::one(), Bn128Field::from(1)),
+            (FlatVariable::public(1), Bn128Field::from(6)),
+        ];
+
+        let mut a = Witness::empty();
+        for (variable, value) in entries.iter().cloned() {
+            a.insert(variable, value);
+        }
+        let b = Witness(entries.into_iter().rev().collect());
+        assert_eq!(a, b);
+
+        let order = |w: &Witness<Bn128Field>| {
+            w.iter()
+                .map(|(v, value)| (v.to_string(), value.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(order(&a), order(&b));
+        assert_eq!(
+            order(&a).into_iter().map(|(v, _)| v).collect::<Vec<_>>(),
+            vec!["~out_1", "~out_0", "~one", "_0", "_42"]
+        );
+    }
+
+    mod merge {
+        use super::*;
+        use crate::ir::{Interpreter, ProgBuilder};
+        use crate::testing::{assert_prove_verify, identity};
+
+        #[test]
+        fn identities() {
+            let first = Interpreter::default()
+                .execute(&identity(true), &vec![Bn128Field::from(3)])
+                .unwrap();
+            let second = Interpreter::default()
+                .execute(&identity(true), &vec![Bn128Field::from(4)])
+                .unwrap();
+
+            // the second identity reads `_1` and returns `~out_1` in the composed circuit
+            let mut builder = ProgBuilder::new();
+            let a = builder.private_input();
+            let b = builder.private_input();
+            builder.output(a);
+            builder.output(b);
+            let composed = builder.build().unwrap();
+
+            let rename = vec![
+                (FlatVariable::new(0), FlatVariable::new(1)),
+                (FlatVariable::public(0), FlatVariable::public(1)),
+            ]
+            .into_iter()
+            .collect();
+
+            let merged = first.clone().merge(second.clone(), &rename).unwrap();
+            assert_eq!(composed.check_constraints(&merged), Ok(()));
+
+            let inputs = vec![Bn128Field::from(3), Bn128Field::from(4)];
+            assert_eq!(
+                merged,
+                Interpreter::default().execute(&composed, &inputs).unwrap()
+            );
+            assert_prove_verify(composed, &inputs);
+
+            assert_eq!(
+                first.merge(second, &BTreeMap::new()),
+                Err(MergeError {
+                    variable: FlatVariable::public(0)
+                })
+            );
+        }
+    }
+
     mod io {
         use super::*;
+        use crate::testing::identity;
         use std::io::Cursor;
 
         #[test]
@@ -134,6 +393,49 @@ mod tests {
             assert_eq!(w, r);
         }
 
+        #[test]
+        fn zokrates_text() {
+            use crate::ir::Prog;
+            use crate::testing::assert_witness_verifies;
+
+            let text = "~one 1\n_0  42\n\n~out_0 42\n";
+            let w = Witness::<Bn128Field>::from_zokrates_text(text.as_bytes()).unwrap();
+            assert_eq!(w.0.len(), 3);
+            assert_eq!(w.0[&FlatVariable::new(0)], Bn128Field::from(42));
+
+            let program: Prog<Bn128Field> = identity(true);
+
+            assert_witness_verifies(program, w);
+
+            assert!(Witness::<Bn128Field>::from_zokrates_text("_0 1\n_0 2".as_bytes()).is_err());
+            assert!(Witness::<Bn128Field>::from_zokrates_text("_0 1 2".as_bytes()).is_err());
+        }
+
+        #[test]
+        fn zokrates_text_round_trip() {
+            let w = Witness(
+                vec![
+                    (FlatVariable::new(42), Bn128Field::from(42)),
+                    (FlatVariable::new(3), Bn128Field::from(3)),
+                    (FlatVariable::public(1), Bn128Field::from(-1)),
+                    (FlatVariable::public(0), Bn128Field::from(0)),
+                    (FlatVariable::one(), Bn128Field::from(1)),
+                ]
+                .into_iter()
+                .collect(),
+            );
+
+            let text = w.to_zokrates_text();
+            assert_eq!(
+                text.lines()
+                    .map(|l| l.split(' ').next().unwrap())
+                    .collect::<Vec<_>>(),
+                vec!["~one", "_3", "_42", "~out_0", "~out_1"]
+            );
+
+            assert_eq!(Witness::from_zokrates_text(text.as_bytes()).unwrap(), w);
+        }
+
         #[test]
         fn wrong_value() {
             let mut buff = Cursor::new(vec![]);
@@ -12,10 +12,13 @@ extern crate bellman_ce as bellman;
 extern crate bincode;
 extern crate csv;
 extern crate ff_ce as ff;
+#[cfg(feature = "wasm")]
+extern crate getrandom;
 extern crate hex;
 extern crate lazy_static;
 extern crate pairing_ce as pairing;
 extern crate regex;
+extern crate sha2;
 extern crate zokrates_common;
 extern crate zokrates_field;
 extern crate zokrates_pest_ast;
@@ -32,8 +35,12 @@ mod static_analysis;
 mod zir;
 
 pub mod absy;
+pub mod command;
 pub mod compile;
+pub mod error;
 pub mod flat_absy;
 pub mod ir;
 pub mod proof_system;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod typed_absy;
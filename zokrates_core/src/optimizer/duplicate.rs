@@ -11,7 +11,15 @@ fn hash<T: Field>(s: &Statement<T>) -> Hash {
     use std::hash::Hash;
     use std::hash::Hasher;
     let mut hasher = DefaultHasher::new();
-    s.hash(&mut hasher);
+    match s {
+        // hash constraints in normal form, so that constraints which only differ in the order of their
+        // terms or of the factors of their quadratic part are considered equal
+        Statement::Constraint(quad, lin) => {
+            quad.clone().into_normal_form().hash(&mut hasher);
+            lin.clone().into_canonical().hash(&mut hasher);
+        }
+        Statement::Directive(..) => s.hash(&mut hasher),
+    }
     hasher.finish()
 }
 
@@ -136,4 +144,48 @@ mod tests {
 
         assert_eq!(DuplicateOptimizer::optimize(p), expected);
     }
+
+    #[test]
+    fn remove_equivalent_duplicates() {
+        use crate::testing::assert_witness_verifies;
+
+        // _0 * (_1 + _0) == ~out_0
+        let constraint = Statement::Constraint(
+            QuadComb::from_linear_combinations(
+                FlatVariable::new(0).into(),
+                LinComb::from(FlatVariable::new(1)) + LinComb::from(FlatVariable::new(0)),
+            ),
+            FlatVariable::public(0).into(),
+        );
+
+        // (_0 + _1) * _0 == ~out_0
+        let equivalent = Statement::Constraint(
+            QuadComb::from_linear_combinations(
+                LinComb::from(FlatVariable::new(0)) + LinComb::from(FlatVariable::new(1)),
+                FlatVariable::new(0).into(),
+            ),
+            FlatVariable::public(0).into(),
+        );
+
+        let p: Prog<Bn128Field> = Prog {
+            private: vec![false, true],
+            main: Function {
+                id: "main".to_string(),
+                statements: vec![constraint.clone(), equivalent],
+                returns: vec![FlatVariable::public(0)],
+                arguments: vec![FlatVariable::new(0), FlatVariable::new(1)],
+            },
+        };
+
+        let optimized = p.dedup_constraints();
+
+        assert_eq!(optimized.main.statements, vec![constraint]);
+
+        let witness = Interpreter::default()
+            .execute(&optimized, &vec![Bn128Field::from(2), Bn128Field::from(3)])
+            .unwrap();
+        assert_eq!(witness.return_values(), vec![Bn128Field::from(10)]);
+
+        assert_witness_verifies(optimized, witness);
+    }
 }
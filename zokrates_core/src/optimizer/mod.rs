@@ -29,4 +29,9 @@ impl<T: Field> Prog<T> {
         let r = DuplicateOptimizer::optimize(r);
         r
     }
+
+    /// Removes duplicate constraints, keeping the first occurrence of each
+    pub fn dedup_constraints(self) -> Self {
+        DuplicateOptimizer::optimize(self)
+    }
 }
@@ -0,0 +1,82 @@
+//! Base64 with the standard alphabet and padding, as defined in RFC 4648
+
+use std::io;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut res = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | ((*b as u32) << (16 - 8 * i)));
+
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => res.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char),
+                false => res.push('='),
+            }
+        }
+    }
+
+    res
+}
+
+pub fn decode(s: &str) -> io::Result<Vec<u8>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Invalid base64");
+
+    let s = s.as_bytes();
+    if s.len() % 4 != 0 {
+        return Err(invalid());
+    }
+
+    let mut res = Vec::with_capacity(s.len() / 4 * 3);
+
+    for (index, chunk) in s.chunks(4).enumerate() {
+        let is_last = index == s.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+        if padding > 2 || (padding > 0 && !is_last) {
+            return Err(invalid());
+        }
+
+        let mut n = 0u32;
+        for (i, c) in chunk[..4 - padding].iter().enumerate() {
+            let value = ALPHABET.iter().position(|a| a == c).ok_or_else(invalid)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+
+        res.extend(n.to_be_bytes()[1..4 - padding].iter());
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc_vectors() {
+        // test vectors from RFC 4648, section 10
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (decoded, encoded) in vectors.iter() {
+            assert_eq!(encode(decoded.as_bytes()), *encoded);
+            assert_eq!(decode(encoded).unwrap(), decoded.as_bytes());
+        }
+
+        assert!(decode("Zm9").is_err());
+        assert!(decode("Zg==Zm8=").is_err());
+        assert!(decode("Zm9*").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+//! Aggregation of Groth16 proofs.
+//!
+//! This is a stub: it fixes the types and signature of the aggregation API so that downstream
+//! code can be written against it, but no aggregation scheme is implemented yet and
+//! `aggregate_proofs` always fails with `AggregationError::Unsupported`.
+
+use bellman::groth16::{Proof, VerifyingKey};
+use bellman::pairing::bn256::Bn256;
+use std::fmt;
+
+/// A single proof attesting to the validity of a batch of Groth16 proofs
+#[derive(Debug)]
+pub struct AggregatedProof {
+    _private: (),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AggregationError {
+    Unsupported,
+}
+
+impl fmt::Display for AggregationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AggregationError::Unsupported => write!(f, "Proof aggregation is not supported yet"),
+        }
+    }
+}
+
+/// Aggregates `proofs`, each verified by the verifying key at the same index in `vks`
+///
+/// Always returns `AggregationError::Unsupported` for now.
+pub fn aggregate_proofs(
+    _proofs: &[Proof<Bn256>],
+    _vks: &[VerifyingKey<Bn256>],
+) -> Result<AggregatedProof, AggregationError> {
+    Err(AggregationError::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(
+            aggregate_proofs(&[], &[]).unwrap_err(),
+            AggregationError::Unsupported
+        );
+    }
+}
@@ -2,23 +2,61 @@ use bellman::groth16::{
     prepare_verifying_key, verify_proof, Parameters, PreparedVerifyingKey, Proof as BellmanProof,
     VerifyingKey,
 };
-use pairing::{CurveAffine, Engine};
+use bellman::pairing::bn256::{Bn256, Fr};
+use bellman::pairing::ff::{Field as FfField, PrimeField, PrimeFieldRepr, ScalarEngine};
+use num_bigint::BigUint;
+use pairing::{CurveAffine, CurveProjective, EncodedPoint, Engine};
 use regex::Regex;
+use std::io::{self, Read, Write};
 
-use zokrates_field::Field;
+use zokrates_field::{Bn128Field, Field};
 
+use crate::error::ZokratesError;
 use crate::ir;
+use crate::proof_system::base64;
+use crate::proof_system::bellman::rand::Rng;
 use crate::proof_system::bellman::Computation;
 use crate::proof_system::bellman::{parse_fr, parse_g1, parse_g2};
+use crate::proof_system::keccak::keccak256;
 use crate::proof_system::solidity::{
     SOLIDITY_G2_ADDITION_LIB, SOLIDITY_PAIRING_LIB, SOLIDITY_PAIRING_LIB_V2,
 };
-use proof_system::{G1Affine, G2Affine, Proof, ProofSystem, SetupKeypair, SolidityAbi};
+use proof_system::{
+    G1Affine, G2Affine, InputError, PointError, Proof, ProofSystem, SetupKeypair, SolidityAbi,
+    VerificationError, VerifyResult,
+};
 
 const G16_WARNING: &str = "WARNING: You are using the G16 scheme which is subject to malleability. See zokrates.github.io/toolbox/proving_schemes.html#g16-malleability for implications.";
 
 pub struct G16 {}
 
+// gas costs of the alt_bn128 precompiles as of EIP-1108
+const EC_ADD_GAS: usize = 150;
+const EC_MUL_GAS: usize = 6000;
+const PAIRING_BASE_GAS: usize = 45000;
+const PAIRING_PER_PAIR_GAS: usize = 34000;
+const TX_BASE_GAS: usize = 21000;
+
+/// Estimates the gas cost of a call to `verifyTx` for a verification key with `gamma_abc_count` entries.
+///
+/// `gas = 21000 + (gamma_abc_count - 1) * (6000 + 150) + 150 + 45000 + 4 * 34000`
+///
+/// This covers the base transaction cost, a scalar multiplication and an addition for each public input,
+/// the addition of `gamma_abc[0]` and the final pairing check over four pairs. Calldata, memory and
+/// the construction of the verification key are not accounted for, so the actual cost is higher.
+///
+/// Returns `None` if `gamma_abc_count` is zero, as a well-formed verification key always has the point of the
+/// constant term.
+pub fn estimate_verification_gas(gamma_abc_count: usize) -> Option<usize> {
+    gamma_abc_count.checked_sub(1).map(|input_count| {
+        TX_BASE_GAS
+            + input_count * (EC_MUL_GAS + EC_ADD_GAS)
+            + EC_ADD_GAS
+            + PAIRING_BASE_GAS
+            + 4 * PAIRING_PER_PAIR_GAS
+    })
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ProofPoints {
     a: G1Affine,
@@ -27,6 +65,14 @@ pub struct ProofPoints {
 }
 
 impl ProofPoints {
+    pub fn from_bellman<T: Field>(proof: &BellmanProof<T::BellmanEngine>) -> Self {
+        ProofPoints {
+            a: parse_g1::<T>(&proof.a),
+            b: parse_g2::<T>(&proof.b),
+            c: parse_g1::<T>(&proof.c),
+        }
+    }
+
     fn into_bellman<T: Field>(self) -> BellmanProof<T::BellmanEngine> {
         BellmanProof {
             a: serialization::to_g1::<T>(self.a),
@@ -46,6 +92,16 @@ pub struct VerificationKey {
 }
 
 impl VerificationKey {
+    pub fn from_bellman<T: Field>(vk: &VerifyingKey<T::BellmanEngine>) -> Self {
+        VerificationKey {
+            alpha: parse_g1::<T>(&vk.alpha_g1),
+            beta: parse_g2::<T>(&vk.beta_g2),
+            gamma: parse_g2::<T>(&vk.gamma_g2),
+            delta: parse_g2::<T>(&vk.delta_g2),
+            gamma_abc: vk.ic.iter().map(|g1| parse_g1::<T>(g1)).collect(),
+        }
+    }
+
     fn into_bellman<T: Field>(self) -> VerifyingKey<T::BellmanEngine> {
         VerifyingKey {
             alpha_g1: serialization::to_g1::<T>(self.alpha),
@@ -63,6 +119,705 @@ impl VerificationKey {
     }
 }
 
+/// Computes a fingerprint of a verifying key, so that a proof can record which key it was generated for.
+/// `beta_g1` and `delta_g1` are not part of the exported verification key, so they are left out.
+pub fn vk_fingerprint<T: Field>(vk: &VerifyingKey<T::BellmanEngine>) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.input(vk.alpha_g1.into_uncompressed());
+    hasher.input(vk.beta_g2.into_uncompressed());
+    hasher.input(vk.gamma_g2.into_uncompressed());
+    hasher.input(vk.delta_g2.into_uncompressed());
+    for g1 in &vk.ic {
+        hasher.input(g1.into_uncompressed());
+    }
+
+    let mut res = [0u8; 32];
+    res.copy_from_slice(&hasher.result());
+    res
+}
+
+/// Returns the point of `vk` which the public input at index `i` is multiplied with during verification, or `None`
+/// if `vk` has fewer than `i + 1` public inputs. The first point of `vk.ic` is not associated with any input.
+pub fn ic_for_input<E: Engine>(vk: &VerifyingKey<E>, i: usize) -> Option<E::G1Affine> {
+    vk.ic.get(i + 1).cloned()
+}
+
+/// Returns the number of public inputs `vk` expects, or `None` if it does not even have the point of the constant
+/// term, which only happens for a malformed deserialized key. Functions taking inputs report such a key as
+/// expecting none, so that no input count is accepted.
+fn input_count<E: Engine>(vk: &VerifyingKey<E>) -> Option<usize> {
+    vk.ic.len().checked_sub(1)
+}
+
+/// Checks that `vk` was generated for a program with `count` public inputs. A verification key gains a point
+/// whenever its program gains a public input, so a key deployed for an older version of a program fails here
+/// rather than rejecting every proof.
+pub fn check_input_count<E: Engine>(
+    vk: &VerifyingKey<E>,
+    count: usize,
+) -> Result<(), VerificationError> {
+    let expected = input_count(vk).ok_or(VerificationError::WrongInputCount {
+        expected: 0,
+        got: count,
+    })?;
+    match count == expected {
+        true => Ok(()),
+        false => Err(VerificationError::WrongInputCount {
+            expected,
+            got: count,
+        }),
+    }
+}
+
+/// Writes a verifying key so that a prepared verifying key can be loaded with `read_pvk`.
+///
+/// bellman does not expose the contents of `PreparedVerifyingKey`, so the verifying key itself is written
+/// and prepared again on load. This saves parsing and checking the verification key points, but the
+/// pairing computed by `prepare_verifying_key` is repeated each time the key is read.
+pub fn write_pvk<T: Field, W: Write>(vk: &VerifyingKey<T::BellmanEngine>, w: W) -> io::Result<()> {
+    vk.write(w)
+}
+
+/// Returns the number of bytes written by `write_pvk` for `vk`: six uncompressed points, the number of elements of
+/// `ic` on 4 bytes and the elements of `ic`
+pub fn vk_size_bytes(vk: &VerifyingKey<Bn256>) -> usize {
+    let g1 = <<Bn256 as Engine>::G1Affine as CurveAffine>::Uncompressed::size();
+    let g2 = <<Bn256 as Engine>::G2Affine as CurveAffine>::Uncompressed::size();
+
+    3 * g1 + 3 * g2 + 4 + vk.ic.len() * g1
+}
+
+/// Reads a verifying key written by `write_pvk` and prepares it for verification
+pub fn read_pvk<T: Field, R: Read>(r: R) -> io::Result<PreparedVerifyingKey<T::BellmanEngine>> {
+    let vk = VerifyingKey::read(r)?;
+    Ok(prepare_verifying_key(&vk))
+}
+
+/// Writes field elements as a 4-byte big-endian count followed by each element in big-endian order, on 32 bytes
+/// for the supported curves
+pub fn write_inputs<T: Field, W: Write>(
+    inputs: &[<T::BellmanEngine as ScalarEngine>::Fr],
+    mut w: W,
+) -> io::Result<()> {
+    w.write_all(&(inputs.len() as u32).to_be_bytes())?;
+    for input in inputs {
+        input.into_repr().write_be(&mut w)?;
+    }
+    Ok(())
+}
+
+/// Reads field elements written by `write_inputs`, failing on elements which are not reduced
+pub fn read_inputs<T: Field, R: Read>(
+    mut r: R,
+) -> io::Result<Vec<<T::BellmanEngine as ScalarEngine>::Fr>> {
+    let mut count = [0u8; 4];
+    r.read_exact(&mut count)?;
+
+    (0..u32::from_be_bytes(count))
+        .map(|_| {
+            let mut repr = <<T::BellmanEngine as ScalarEngine>::Fr as PrimeField>::Repr::default();
+            repr.read_be(&mut r)?;
+            <T::BellmanEngine as ScalarEngine>::Fr::from_repr(repr)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+        })
+        .collect()
+}
+
+/// Encodes `proof` as base64, using the binary format of bellman
+pub fn proof_to_base64(proof: &BellmanProof<Bn256>) -> String {
+    let mut bytes = vec![];
+    proof.write(&mut bytes).unwrap();
+    base64::encode(&bytes)
+}
+
+/// Decodes a proof encoded by `proof_to_base64`
+pub fn proof_from_base64(s: &str) -> io::Result<BellmanProof<Bn256>> {
+    BellmanProof::read(&base64::decode(s)?[..])
+}
+
+/// Encodes `vk` as base64, using the binary format of bellman
+pub fn vk_to_base64(vk: &VerifyingKey<Bn256>) -> String {
+    let mut bytes = vec![];
+    vk.write(&mut bytes).unwrap();
+    base64::encode(&bytes)
+}
+
+/// Decodes a verifying key encoded by `vk_to_base64`
+pub fn vk_from_base64(s: &str) -> io::Result<VerifyingKey<Bn256>> {
+    VerifyingKey::read(&base64::decode(s)?[..])
+}
+
+fn vyper_g1(p: &G1Affine) -> String {
+    format!("G1Point({{x: {}, y: {}}})", p.0, p.1)
+}
+
+fn vyper_g2(p: &G2Affine) -> String {
+    format!(
+        "G2Point({{x: [{}, {}], y: [{}, {}]}})",
+        (p.0).0,
+        (p.0).1,
+        (p.1).0,
+        (p.1).1
+    )
+}
+
+/// Exports a Vyper verifier for `vk`, equivalent to the ABI v1 Solidity verifier: `verifyTx` takes the same
+/// arguments and the curve operations are delegated to the bn128 precompiles through `raw_call`.
+pub fn export_vyper_verifier(vk: &VerifyingKey<Bn256>) -> Result<String, VerificationError> {
+    let input_count = input_count(vk).ok_or(VerificationError::MalformedKey)?;
+    let vk = VerificationKey::from_bellman::<Bn128Field>(vk);
+
+    // take and check input values only if there are any, as Vyper does not allow empty arrays
+    let (input_argument, input_loop) = match input_count {
+        0 => (String::new(), String::new()),
+        n => (
+            format!(", input: uint256[{}]", n),
+            format!(
+                r#"
+    for i in range({}):
+        assert input[i] < SNARK_SCALAR_FIELD
+        vk_x = self.addition(vk_x, self.scalar_mul(gamma_abc[i + 1], input[i]))"#,
+                n
+            ),
+        ),
+    };
+
+    let gamma_abc_pts = vk
+        .gamma_abc
+        .iter()
+        .map(vyper_g1)
+        .collect::<Vec<_>>()
+        .join(",\n        ");
+
+    let template_text = VYPER_CONTRACT_TEMPLATE
+        .replace("<%vk_alpha%>", &vyper_g1(&vk.alpha))
+        .replace("<%vk_beta%>", &vyper_g2(&vk.beta))
+        .replace("<%vk_gamma%>", &vyper_g2(&vk.gamma))
+        .replace("<%vk_delta%>", &vyper_g2(&vk.delta))
+        .replace("<%vk_gamma_abc_length%>", &vk.gamma_abc.len().to_string())
+        .replace("<%vk_gamma_abc_pts%>", &gamma_abc_pts)
+        .replace("<%input_argument%>", &input_argument)
+        .replace("<%input_loop%>", &input_loop);
+
+    // hexadecimal literals of 32 bytes are `bytes32` values in Vyper
+    let re = Regex::new(r"(?P<v>0[xX][0-9a-fA-F]{64})").unwrap();
+    Ok(re
+        .replace_all(&template_text, "convert($v, uint256)")
+        .into_owned())
+}
+
+fn validate_point<G: CurveAffine>(name: &'static str, p: &G) -> Result<(), PointError> {
+    if p.is_zero() {
+        return Ok(());
+    }
+
+    let (x, y) = p.into_xy_unchecked();
+    G::from_xy_checked(x, y).map_err(|_| PointError::NotOnCurve(name))?;
+
+    match p.mul(G::Scalar::char()).is_zero() {
+        true => Ok(()),
+        false => Err(PointError::NotInSubgroup(name)),
+    }
+}
+
+/// Checks that the points of a bn128 proof are on the curve and in the subgroup of prime order, as points of
+/// proofs received from untrusted parties may be built without these checks, for example with
+/// `from_xy_unchecked`. The point at infinity is accepted.
+pub fn validate_proof_points(proof: &BellmanProof<Bn256>) -> Result<(), PointError> {
+    validate_point("A", &proof.a)?;
+    validate_point("B", &proof.b)?;
+    validate_point("C", &proof.c)
+}
+
+/// The arguments of `verifyTx` in the ABI v1 verifier: `uint[2] a, uint[2][2] b, uint[2] c, uint[N] input`
+#[derive(Serialize, Debug, PartialEq)]
+pub struct EthProofArgs {
+    pub a: [String; 2],
+    pub b: [[String; 2]; 2],
+    pub c: [String; 2],
+    pub inputs: Vec<String>,
+}
+
+/// Lays out a bn128 proof and its public inputs as expected by the Solidity verifier.
+///
+/// The alt_bn128 precompile takes each coordinate of a G2 point as `[imaginary, real]`, so the components of
+/// the coordinates of `b` are swapped with respect to bellman's `c0 + c1 * u` representation.
+pub fn serialize_proof_ethereum(proof: &BellmanProof<Bn256>, inputs: &[Fr]) -> EthProofArgs {
+    let a = parse_g1::<Bn128Field>(&proof.a);
+    let b = parse_g2::<Bn128Field>(&proof.b);
+    let c = parse_g1::<Bn128Field>(&proof.c);
+
+    EthProofArgs {
+        a: [a.0, a.1],
+        // `parse_g2` already yields `[c1, c0]` for each coordinate
+        b: [[(b.0).0, (b.0).1], [(b.1).0, (b.1).1]],
+        c: [c.0, c.1],
+        inputs: inputs.iter().map(parse_fr::<Bn128Field>).collect(),
+    }
+}
+
+/// A verifying key laid out as the `verification_key.json` files of snarkjs
+#[derive(Serialize)]
+struct SnarkjsVerificationKey {
+    protocol: &'static str,
+    curve: &'static str,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    vk_alpha_1: [String; 3],
+    vk_beta_2: [[String; 2]; 3],
+    vk_gamma_2: [[String; 2]; 3],
+    vk_delta_2: [[String; 2]; 3],
+    #[serde(rename = "IC")]
+    ic: Vec<[String; 3]>,
+}
+
+fn to_decimal(hex: &str) -> String {
+    BigUint::parse_bytes(hex.trim_start_matches("0x").as_bytes(), 16)
+        .unwrap()
+        .to_str_radix(10)
+}
+
+/// The encoding of the coordinates and inputs written by the snarkjs serializers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// Decimal strings, as written by snarkjs
+    Decimal,
+    /// `0x`-prefixed hexadecimal strings of 64 digits, as preferred by Ethereum tooling
+    Hex,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding::Decimal
+    }
+}
+
+impl Encoding {
+    // `hex` is a `0x`-prefixed hexadecimal string, such as those returned by `parse_g1`
+    fn encode(self, hex: &str) -> String {
+        match self {
+            Encoding::Decimal => to_decimal(hex),
+            Encoding::Hex => format!("0x{:0>64}", hex.trim_start_matches("0x").to_lowercase()),
+        }
+    }
+}
+
+/// A proof laid out as the `proof.json` files of snarkjs
+#[derive(Serialize)]
+struct SnarkjsProof {
+    pi_a: [String; 3],
+    pi_b: [[String; 2]; 3],
+    pi_c: [String; 3],
+    protocol: &'static str,
+    curve: &'static str,
+}
+
+/// A G1 point in projective coordinates with `z = 1`
+fn snarkjs_g1(p: &<Bn256 as Engine>::G1Affine, encoding: Encoding) -> [String; 3] {
+    let p = parse_g1::<Bn128Field>(p);
+    [
+        encoding.encode(&p.0),
+        encoding.encode(&p.1),
+        encoding.encode("0x1"),
+    ]
+}
+
+/// A G2 point in projective coordinates with `z = 1`, each coordinate being given as `[c0, c1]` for `c0 + c1 * u`
+fn snarkjs_g2(p: &<Bn256 as Engine>::G2Affine, encoding: Encoding) -> [[String; 2]; 3] {
+    // `parse_g2` yields `[c1, c0]` for each coordinate
+    let p = parse_g2::<Bn128Field>(p);
+    [
+        [encoding.encode(&(p.0).1), encoding.encode(&(p.0).0)],
+        [encoding.encode(&(p.1).1), encoding.encode(&(p.1).0)],
+        [encoding.encode("0x1"), encoding.encode("0x0")],
+    ]
+}
+
+/// Serializes a bn128 verifying key to the JSON format of snarkjs.
+///
+/// Points are given in projective coordinates with `z = 1`, as decimal strings. Each coordinate of a G2 point
+/// is given as `[c0, c1]` for `c0 + c1 * u`, which is the opposite of the order expected by the EVM.
+pub fn serialize_vk_snarkjs(vk: &VerifyingKey<Bn256>) -> Result<String, VerificationError> {
+    serialize_vk_snarkjs_with_encoding(vk, Encoding::default())
+}
+
+/// Serializes a bn128 verifying key like `serialize_vk_snarkjs`, writing coordinates with `encoding`
+pub fn serialize_vk_snarkjs_with_encoding(
+    vk: &VerifyingKey<Bn256>,
+    encoding: Encoding,
+) -> Result<String, VerificationError> {
+    let snarkjs = SnarkjsVerificationKey {
+        protocol: "groth16",
+        curve: "bn128",
+        n_public: input_count(vk).ok_or(VerificationError::MalformedKey)?,
+        vk_alpha_1: snarkjs_g1(&vk.alpha_g1, encoding),
+        vk_beta_2: snarkjs_g2(&vk.beta_g2, encoding),
+        vk_gamma_2: snarkjs_g2(&vk.gamma_g2, encoding),
+        vk_delta_2: snarkjs_g2(&vk.delta_g2, encoding),
+        ic: vk.ic.iter().map(|p| snarkjs_g1(p, encoding)).collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&snarkjs).unwrap())
+}
+
+/// Serializes a bn128 proof and its public inputs to the JSON format of snarkjs, returning the contents of
+/// `proof.json` and `public.json`. Points are laid out as in `serialize_vk_snarkjs`, and inputs are decimal strings.
+pub fn serialize_proof_snarkjs(proof: &BellmanProof<Bn256>, inputs: &[Fr]) -> (String, String) {
+    serialize_proof_snarkjs_with_encoding(proof, inputs, Encoding::default())
+}
+
+/// Serializes a bn128 proof and its public inputs like `serialize_proof_snarkjs`, writing coordinates and inputs
+/// with `encoding`
+pub fn serialize_proof_snarkjs_with_encoding(
+    proof: &BellmanProof<Bn256>,
+    inputs: &[Fr],
+    encoding: Encoding,
+) -> (String, String) {
+    let snarkjs = SnarkjsProof {
+        pi_a: snarkjs_g1(&proof.a, encoding),
+        pi_b: snarkjs_g2(&proof.b, encoding),
+        pi_c: snarkjs_g1(&proof.c, encoding),
+        protocol: "groth16",
+        curve: "bn128",
+    };
+
+    let public: Vec<String> = inputs
+        .iter()
+        .map(|i| encoding.encode(&parse_fr::<Bn128Field>(i)))
+        .collect();
+
+    (
+        serde_json::to_string_pretty(&snarkjs).unwrap(),
+        serde_json::to_string_pretty(&public).unwrap(),
+    )
+}
+
+/// Commits to a list of bn128 public inputs, as `keccak256(abi.encodePacked(inputs))` would in a Solidity
+/// contract taking them as `uint[N] inputs`.
+///
+/// The hashed bytes are the concatenation of the inputs, each encoded as a 32-byte big-endian integer in
+/// canonical form, i.e. smaller than the field modulus. No length prefix is added.
+pub fn public_inputs_hash(inputs: &[Fr]) -> [u8; 32] {
+    let mut bytes = Vec::with_capacity(inputs.len() * 32);
+    for input in inputs {
+        input.into_repr().write_be(&mut bytes).unwrap();
+    }
+
+    keccak256(&bytes)
+}
+
+/// Re-randomizes a bn128 proof into a fresh proof of the same statement, without access to the witness.
+///
+/// For random `r != 0` and `s`, `A' = (1/r) A`, `B' = r B + r s delta` and `C' = C + s A`. The pairing
+/// equation still holds since `e(A', B') = e(A, B) e(s A, delta)`, so the new proof verifies against the same
+/// inputs while being unlinkable to the original one.
+pub fn rerandomize_proof<R: Rng>(
+    proof: &BellmanProof<Bn256>,
+    vk: &VerifyingKey<Bn256>,
+    rng: &mut R,
+) -> BellmanProof<Bn256> {
+    let r: Fr = std::iter::repeat_with(|| rng.gen())
+        .find(|r: &Fr| !r.is_zero())
+        .unwrap();
+    let s: Fr = rng.gen();
+
+    let mut rs = r;
+    rs.mul_assign(&s);
+
+    let a_prime = proof.a.mul(r.inverse().unwrap());
+    let mut b_prime = proof.b.mul(r);
+    b_prime.add_assign(&vk.delta_g2.mul(rs));
+    let mut c_prime = proof.a.mul(s);
+    c_prime.add_assign_mixed(&proof.c);
+
+    BellmanProof {
+        a: a_prime.into_affine(),
+        b: b_prime.into_affine(),
+        c: c_prime.into_affine(),
+    }
+}
+
+/// Returns true if both proofs have the same binary serialization
+pub fn proofs_equal(a: &BellmanProof<Bn256>, b: &BellmanProof<Bn256>) -> bool {
+    let bytes = |p: &BellmanProof<Bn256>| {
+        let mut bytes = vec![];
+        p.write(&mut bytes).unwrap();
+        bytes
+    };
+
+    bytes(a) == bytes(b)
+}
+
+/// Verifies several proofs against the same verification key, returning whether each of them is valid. The key is
+/// only prepared once. Each proof is still checked with its own pairing equation rather than combined with the
+/// others, so the soundness of each result is the same as that of `verify_proof`. Proofs with the wrong number of
+/// inputs are reported as invalid.
+pub fn verify_batch(
+    vk: &VerifyingKey<Bn256>,
+    items: &[(BellmanProof<Bn256>, Vec<Fr>)],
+) -> Vec<bool> {
+    let pvk = prepare_verifying_key(vk);
+
+    items
+        .iter()
+        .map(|(proof, inputs)| verify_proof(&pvk, proof, inputs).unwrap_or(false))
+        .collect()
+}
+
+/// The hash of a phase-2 contribution, binding the parameters before and after it. As each contribution starts
+/// from the output of the previous one, the hashes of a setup form a chain which participants can publish.
+///
+/// The hash is only `sha256(before || after)`: it identifies a contribution but does not prove who made it or that
+/// its author knows the secret it applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContributionHash(pub [u8; 32]);
+
+impl ContributionHash {
+    fn new(before: &Parameters<Bn256>, after: &Parameters<Bn256>) -> Self {
+        use sha2::{Digest, Sha256};
+
+        let mut bytes = vec![];
+        before.write(&mut bytes).unwrap();
+        after.write(&mut bytes).unwrap();
+
+        let mut res = [0u8; 32];
+        res.copy_from_slice(&Sha256::digest(&bytes));
+        ContributionHash(res)
+    }
+}
+
+/// Contributes to the circuit-specific phase of a trusted setup by multiplying `delta` by a secret `d` derived from
+/// `entropy`, dividing the `h` and `l` queries by `d` accordingly. Returns the new parameters and the hash of the
+/// contribution.
+///
+/// This is NOT a secure MPC ceremony: no proof of knowledge of `d` is produced, as in the ceremony of Bowe, Gabizon
+/// and Miers, so nothing ties a contribution to its author or shows that they built upon the previous parameters
+/// rather than replacing them with ones they control. It is only suitable for testing the shape of a multi-party
+/// setup.
+pub fn contribute(
+    params: Parameters<Bn256>,
+    entropy: &[u8],
+) -> (Parameters<Bn256>, ContributionHash) {
+    use crate::proof_system::bellman::rng_from_seed_bytes;
+    use sha2::{Digest, Sha256};
+    use std::sync::Arc;
+
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&Sha256::digest(entropy));
+    let rng = &mut rng_from_seed_bytes(&seed);
+
+    let d: Fr = std::iter::repeat_with(|| rng.gen())
+        .find(|d: &Fr| !d.is_zero())
+        .unwrap();
+    let d_inverse = d.inverse().unwrap();
+
+    let divide = |query: &[<Bn256 as Engine>::G1Affine]| {
+        Arc::new(
+            query
+                .iter()
+                .map(|p| p.mul(d_inverse).into_affine())
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    let mut vk = params.vk.clone();
+    vk.delta_g1 = vk.delta_g1.mul(d).into_affine();
+    vk.delta_g2 = vk.delta_g2.mul(d).into_affine();
+
+    let after = Parameters {
+        vk,
+        h: divide(&params.h),
+        l: divide(&params.l),
+        a: params.a.clone(),
+        b_g1: params.b_g1.clone(),
+        b_g2: params.b_g2.clone(),
+    };
+
+    let hash = ContributionHash::new(&params, &after);
+    (after, hash)
+}
+
+/// Checks that `after` results from a contribution to `before` with hash `hash`, without knowledge of the secret of
+/// the contributor: all elements but `delta` and the `h` and `l` queries are left unchanged, `delta` is multiplied by
+/// the same value in both groups, and the queries are divided by that value.
+///
+/// This only checks the shape of the transformation. As `contribute` does not produce a proof of knowledge of its
+/// secret, passing this check does not show that the contributor knows it, and a chain of checked contributions is
+/// not the transcript of a secure MPC ceremony.
+pub fn verify_contribution(
+    before: &Parameters<Bn256>,
+    after: &Parameters<Bn256>,
+    hash: &ContributionHash,
+) -> bool {
+    let unchanged = before.vk.alpha_g1 == after.vk.alpha_g1
+        && before.vk.beta_g1 == after.vk.beta_g1
+        && before.vk.beta_g2 == after.vk.beta_g2
+        && before.vk.gamma_g2 == after.vk.gamma_g2
+        && before.vk.ic == after.vk.ic
+        && before.a == after.a
+        && before.b_g1 == after.b_g1
+        && before.b_g2 == after.b_g2
+        && before.h.len() == after.h.len()
+        && before.l.len() == after.l.len();
+
+    // e(delta' G1, delta G2) == e(delta G1, delta' G2) iff both deltas were multiplied by the same value
+    let delta_consistent = !after.vk.delta_g1.is_zero()
+        && Bn256::pairing(after.vk.delta_g1, before.vk.delta_g2)
+            == Bn256::pairing(before.vk.delta_g1, after.vk.delta_g2);
+
+    // e(x / d, delta' G2) == e(x, delta G2) for each element of the queries
+    let queries_consistent = || {
+        before
+            .h
+            .iter()
+            .zip(after.h.iter())
+            .chain(before.l.iter().zip(after.l.iter()))
+            .all(|(b, a)| {
+                Bn256::pairing(*a, after.vk.delta_g2) == Bn256::pairing(*b, before.vk.delta_g2)
+            })
+    };
+
+    *hash == ContributionHash::new(before, after)
+        && unchanged
+        && delta_consistent
+        && queries_consistent()
+}
+
+impl G16 {
+    /// Verifies a proof, failing with `VerificationError::VkMismatch` if the proof was generated for
+    /// another verification key, and with `VerificationError::WrongInputCount` if the proof does not have as
+    /// many public inputs as the verification key. Proofs which do not record a verification key are verified
+    /// as usual.
+    pub fn verify_checked<T: Field>(
+        vk: VerificationKey,
+        proof: Proof<ProofPoints>,
+    ) -> Result<bool, VerificationError> {
+        let vk = vk.into_bellman::<T>();
+
+        check_input_count(&vk, proof.inputs.len())?;
+
+        let expected = hex::encode(vk_fingerprint::<T>(&vk));
+
+        match proof.vk_id {
+            Some(ref found) if *found != expected => Err(VerificationError::VkMismatch {
+                expected,
+                found: found.clone(),
+            }),
+            _ => Ok(Self::verify_bellman::<T>(&vk, proof)),
+        }
+    }
+
+    /// Exports a Solidity verifier like `export_solidity_verifier`, declaring the structural hash of `program` as
+    /// `CIRCUIT_ID` in the contract so that callers can check which circuit the verifier was generated for.
+    /// The identifier does not depend on any witness, and `vk` is expected to come from the setup of `program`.
+    pub fn export_solidity_verifier_with_circuit_id<T: Field>(
+        vk: VerificationKey,
+        abi: SolidityAbi,
+        program: &ir::Prog<T>,
+    ) -> String {
+        <G16 as ProofSystem<T>>::export_solidity_verifier(vk, abi).replacen(
+            "contract Verifier {",
+            &format!(
+                "contract Verifier {{\n    bytes32 public constant CIRCUIT_ID = 0x{};",
+                hex::encode(program.structural_hash())
+            ),
+            1,
+        )
+    }
+
+    /// Verifies a proof, reporting which check failed instead of a plain boolean
+    pub fn verify_explain<T: Field>(
+        vk: &VerifyingKey<T::BellmanEngine>,
+        proof: &BellmanProof<T::BellmanEngine>,
+        inputs: &[<T::BellmanEngine as ScalarEngine>::Fr],
+    ) -> VerifyResult {
+        // the first element of `ic` is not associated with any input, and a key without it accepts no input count
+        let expected = match input_count(vk) {
+            Some(expected) => expected,
+            None => {
+                return VerifyResult::WrongInputCount {
+                    expected: 0,
+                    got: inputs.len(),
+                }
+            }
+        };
+        if inputs.len() != expected {
+            return VerifyResult::WrongInputCount {
+                expected,
+                got: inputs.len(),
+            };
+        }
+
+        let pvk = prepare_verifying_key(vk);
+
+        match verify_proof(&pvk, proof, inputs).unwrap() {
+            true => VerifyResult::Ok,
+            false => VerifyResult::PairingFailed,
+        }
+    }
+
+    /// Verifies a proof against public inputs given as strings, such as the `inputs` array of a proof in JSON.
+    /// Inputs may be decimal or `0x`-prefixed hexadecimal, and values are not reduced modulo the field: a value
+    /// which is not smaller than the modulus is rejected.
+    pub fn verify_with_string_inputs<T: Field>(
+        vk: &VerifyingKey<T::BellmanEngine>,
+        proof: &BellmanProof<T::BellmanEngine>,
+        inputs: &[String],
+    ) -> Result<bool, InputError> {
+        let expected = input_count(vk).ok_or(InputError::WrongCount {
+            expected: 0,
+            got: inputs.len(),
+        })?;
+        if inputs.len() != expected {
+            return Err(InputError::WrongCount {
+                expected,
+                got: inputs.len(),
+            });
+        }
+
+        let inputs = inputs
+            .iter()
+            .map(|s| {
+                let value = match s.starts_with("0x") {
+                    true => BigUint::parse_bytes(s[2..].as_bytes(), 16),
+                    false => BigUint::parse_bytes(s.as_bytes(), 10),
+                }
+                .ok_or_else(|| InputError::Invalid(s.clone()))?;
+
+                match value < T::modulus() {
+                    true => Ok(T::from(value).into_bellman()),
+                    false => Err(InputError::OutOfRange(s.clone())),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(verify_proof(&prepare_verifying_key(vk), proof, &inputs).unwrap())
+    }
+
+    fn verify_bellman<T: Field>(
+        vk: &VerifyingKey<T::BellmanEngine>,
+        proof: Proof<ProofPoints>,
+    ) -> bool {
+        let pvk: PreparedVerifyingKey<T::BellmanEngine> = prepare_verifying_key(vk);
+
+        let bellman_proof: BellmanProof<T::BellmanEngine> = proof.proof.into_bellman::<T>();
+
+        let public_inputs: Vec<_> = proof
+            .inputs
+            .iter()
+            .map(|s| {
+                T::try_from_str(s.trim_start_matches("0x"), 16)
+                    .expect(format!("Invalid {} value: {}", T::name(), s).as_str())
+                    .into_bellman()
+            })
+            .collect::<Vec<_>>();
+
+        verify_proof(&pvk, &bellman_proof, &public_inputs).unwrap()
+    }
+}
+
 impl<T: Field> ProofSystem<T> for G16 {
     type VerificationKey = VerificationKey;
     type ProofPoints = ProofPoints;
@@ -78,18 +833,7 @@ impl<T: Field> ProofSystem<T> for G16 {
 
         parameters.write(&mut pk).unwrap();
 
-        let vk = VerificationKey {
-            alpha: parse_g1::<T>(&parameters.vk.alpha_g1),
-            beta: parse_g2::<T>(&parameters.vk.beta_g2),
-            gamma: parse_g2::<T>(&parameters.vk.gamma_g2),
-            delta: parse_g2::<T>(&parameters.vk.delta_g2),
-            gamma_abc: parameters
-                .vk
-                .ic
-                .iter()
-                .map(|g1| parse_g1::<T>(g1))
-                .collect(),
-        };
+        let vk = VerificationKey::from_bellman::<T>(&parameters.vk);
 
         SetupKeypair::new(vk, pk)
     }
@@ -99,21 +843,23 @@ impl<T: Field> ProofSystem<T> for G16 {
         witness: ir::Witness<T>,
         proving_key: Vec<u8>,
     ) -> Proof<ProofPoints> {
+        Self::try_generate_proof(program, witness, proving_key).unwrap()
+    }
+
+    fn try_generate_proof(
+        program: ir::Prog<T>,
+        witness: ir::Witness<T>,
+        proving_key: Vec<u8>,
+    ) -> Result<Proof<ProofPoints>, ZokratesError> {
         #[cfg(not(target_arch = "wasm32"))]
         std::env::set_var("BELLMAN_VERBOSE", "0");
 
         println!("{}", G16_WARNING);
 
         let computation = Computation::with_witness(program, witness);
-        let params = Parameters::read(proving_key.as_slice(), true).unwrap();
-
-        let proof = computation.clone().prove(&params);
-
-        let proof_points = ProofPoints {
-            a: parse_g1::<T>(&proof.a),
-            b: parse_g2::<T>(&proof.b),
-            c: parse_g1::<T>(&proof.c),
-        };
+        let params = Parameters::read(proving_key.as_slice(), true).map_err(|why| {
+            ZokratesError::Serialization(format!("Couldn't deserialize proving key: {}", why))
+        })?;
 
         let inputs = computation
             .public_inputs_values()
@@ -121,10 +867,17 @@ impl<T: Field> ProofSystem<T> for G16 {
             .map(parse_fr::<T>)
             .collect::<Vec<_>>();
 
+        let proof = computation.prove(&params);
+
+        let proof_points = ProofPoints::from_bellman::<T>(&proof);
+
         let mut raw: Vec<u8> = Vec::new();
         proof.write(&mut raw).unwrap();
 
-        Proof::<ProofPoints>::new(proof_points, inputs, hex::encode(&raw))
+        Ok(
+            Proof::<ProofPoints>::new(proof_points, inputs, hex::encode(&raw))
+                .with_vk_id(hex::encode(vk_fingerprint::<T>(&params.vk))),
+        )
     }
 
     fn export_solidity_verifier(vk: VerificationKey, abi: SolidityAbi) -> String {
@@ -145,6 +898,7 @@ impl<T: Field> ProofSystem<T> for G16 {
         let vk_input_len_regex = Regex::new(r#"(<%vk_input_length%>)"#).unwrap();
         let input_loop = Regex::new(r#"(<%input_loop%>)"#).unwrap();
         let input_argument = Regex::new(r#"(<%input_argument%>)"#).unwrap();
+        let gas_estimate = Regex::new(r#"(<%gas_estimate%>)"#).unwrap();
 
         template_text = vk_regex
             .replace(template_text.as_str(), vk.alpha.to_string().as_str())
@@ -202,6 +956,23 @@ impl<T: Field> ProofSystem<T> for G16 {
         }
         .to_string();
 
+        template_text = gas_estimate
+            .replace(
+                template_text.as_str(),
+                estimate_verification_gas(gamma_abc_count)
+                    .map(|gas| {
+                        format!(
+                            "// Estimated gas cost of verifyTx with {} public input{}: {} (precompiles and base transaction cost only)",
+                            gamma_abc_count - 1,
+                            if gamma_abc_count == 2 { "" } else { "s" },
+                            gas
+                        )
+                    })
+                    .unwrap_or_default()
+                    .as_str(),
+            )
+            .into_owned();
+
         let mut gamma_abc_repeat_text = String::new();
         for (i, g1) in vk.gamma_abc.iter().enumerate() {
             gamma_abc_repeat_text.push_str(
@@ -231,23 +1002,7 @@ impl<T: Field> ProofSystem<T> for G16 {
     }
 
     fn verify(vk: VerificationKey, proof: Proof<ProofPoints>) -> bool {
-        let vk: VerifyingKey<T::BellmanEngine> = vk.into_bellman::<T>();
-
-        let pvk: PreparedVerifyingKey<T::BellmanEngine> = prepare_verifying_key(&vk);
-
-        let bellman_proof: BellmanProof<T::BellmanEngine> = proof.proof.into_bellman::<T>();
-
-        let public_inputs: Vec<_> = proof
-            .inputs
-            .iter()
-            .map(|s| {
-                T::try_from_str(s.trim_start_matches("0x"), 16)
-                    .expect(format!("Invalid {} value: {}", T::name(), s).as_str())
-                    .into_bellman()
-            })
-            .collect::<Vec<_>>();
-
-        verify_proof(&pvk, &bellman_proof, &public_inputs).unwrap()
+        G16::verify_bellman::<T>(&vk.into_bellman::<T>(), proof)
     }
 }
 
@@ -264,7 +1019,7 @@ mod serialization {
         .unwrap()
     }
     pub fn to_g2<T: Field>(g2: G2Affine) -> <T::BellmanEngine as Engine>::G2Affine {
-        // apparently the order is reversed
+        // coordinates are stored as `(c1, c0)`, see `parse_g2`
         let x = T::new_fq2(&(g2.0).1, &(g2.0).0);
         let y = T::new_fq2(&(g2.1).1, &(g2.1).0);
         <T::BellmanEngine as Engine>::G2Affine::from_xy_checked(x, y).unwrap()
@@ -272,6 +1027,7 @@ mod serialization {
 }
 
 const CONTRACT_TEMPLATE_V2: &str = r#"
+<%gas_estimate%>
 contract Verifier {
     using Pairing for *;
     struct VerifyingKey {
@@ -326,7 +1082,99 @@ contract Verifier {
 }
 "#;
 
+const VYPER_CONTRACT_TEMPLATE: &str = r#"# @version ^0.3.7
+
+struct G1Point:
+    x: uint256
+    y: uint256
+
+# Encoding of field elements is: x[0] * z + x[1]
+struct G2Point:
+    x: uint256[2]
+    y: uint256[2]
+
+PRIME_Q: constant(uint256) = 21888242871839275222246405745257275088696311157297823662689037894645226208583
+SNARK_SCALAR_FIELD: constant(uint256) = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+
+# the bn128 precompiles of EIP-196 and EIP-197
+EC_ADD: constant(address) = 0x0000000000000000000000000000000000000006
+EC_MUL: constant(address) = 0x0000000000000000000000000000000000000007
+EC_PAIRING: constant(address) = 0x0000000000000000000000000000000000000008
+
+@internal
+@pure
+def negate(p: G1Point) -> G1Point:
+    if p.x == 0 and p.y == 0:
+        return p
+    return G1Point({x: p.x, y: PRIME_Q - (p.y % PRIME_Q)})
+
+@internal
+@view
+def addition(p1: G1Point, p2: G1Point) -> G1Point:
+    response: Bytes[64] = raw_call(
+        EC_ADD,
+        concat(convert(p1.x, bytes32), convert(p1.y, bytes32), convert(p2.x, bytes32), convert(p2.y, bytes32)),
+        max_outsize=64,
+        is_static_call=True
+    )
+    return G1Point({x: extract32(response, 0, output_type=uint256), y: extract32(response, 32, output_type=uint256)})
+
+@internal
+@view
+def scalar_mul(p: G1Point, s: uint256) -> G1Point:
+    response: Bytes[64] = raw_call(
+        EC_MUL,
+        concat(convert(p.x, bytes32), convert(p.y, bytes32), convert(s, bytes32)),
+        max_outsize=64,
+        is_static_call=True
+    )
+    return G1Point({x: extract32(response, 0, output_type=uint256), y: extract32(response, 32, output_type=uint256)})
+
+@internal
+@pure
+def encode_pair(p1: G1Point, p2: G2Point) -> Bytes[192]:
+    return concat(
+        convert(p1.x, bytes32),
+        convert(p1.y, bytes32),
+        convert(p2.x[0], bytes32),
+        convert(p2.x[1], bytes32),
+        convert(p2.y[0], bytes32),
+        convert(p2.y[1], bytes32)
+    )
+
+@internal
+@view
+def pairing_prod4(a1: G1Point, a2: G2Point, b1: G1Point, b2: G2Point, c1: G1Point, c2: G2Point, d1: G1Point, d2: G2Point) -> bool:
+    response: Bytes[32] = raw_call(
+        EC_PAIRING,
+        concat(self.encode_pair(a1, a2), self.encode_pair(b1, b2), self.encode_pair(c1, c2), self.encode_pair(d1, d2)),
+        max_outsize=32,
+        is_static_call=True
+    )
+    return extract32(response, 0, output_type=uint256) == 1
+
+@external
+@view
+def verifyTx(a: uint256[2], b: uint256[2][2], c: uint256[2]<%input_argument%>) -> bool:
+    alpha: G1Point = <%vk_alpha%>
+    beta: G2Point = <%vk_beta%>
+    gamma: G2Point = <%vk_gamma%>
+    delta: G2Point = <%vk_delta%>
+    gamma_abc: G1Point[<%vk_gamma_abc_length%>] = [
+        <%vk_gamma_abc_pts%>
+    ]
+    # Compute the linear combination vk_x
+    vk_x: G1Point = gamma_abc[0]<%input_loop%>
+    return self.pairing_prod4(
+        G1Point({x: a[0], y: a[1]}), G2Point({x: b[0], y: b[1]}),
+        self.negate(vk_x), gamma,
+        self.negate(G1Point({x: c[0], y: c[1]})), delta,
+        self.negate(alpha), beta
+    )
+"#;
+
 const CONTRACT_TEMPLATE: &str = r#"
+<%gas_estimate%>
 contract Verifier {
     using Pairing for *;
     struct VerifyingKey {
@@ -390,10 +1238,50 @@ contract Verifier {
 #[cfg(test)]
 mod tests {
     use crate::flat_absy::FlatVariable;
-    use crate::ir::{Function, Interpreter, Prog, Statement};
+    use crate::ir::{Function, Interpreter, LinComb, Prog, ProgBuilder, Statement};
+    use crate::proof_system::bellman::ThreadRandomness;
+    use crate::proof_system::{serialize_proof, ProofMeta};
+    use crate::testing::{identity, identity_proof};
 
     use super::*;
-    use zokrates_field::Bn128Field;
+    use zokrates_field::Bls12Field;
+
+    #[test]
+    fn point_serialization_roundtrip() {
+        use crate::proof_system::bellman::rand::{ChaChaRng, SeedableRng};
+        use bellman::pairing::bn256::{G1Affine as Bn256G1, G2Affine as Bn256G2, G1, G2};
+
+        let rng = &mut ChaChaRng::from_seed(&[42]);
+
+        let g1s = Some(Bn256G1::one())
+            .into_iter()
+            .chain((0..50).map(|_| rng.gen::<G1>().into_affine()))
+            .collect::<Vec<_>>();
+        let g2s = Some(Bn256G2::one())
+            .into_iter()
+            .chain((0..50).map(|_| rng.gen::<G2>().into_affine()))
+            .collect::<Vec<_>>();
+
+        for point in g1s {
+            let json = serde_json::to_string(&parse_g1::<Bn128Field>(&point)).unwrap();
+            let parsed: G1Affine = serde_json::from_str(&json).unwrap();
+            assert_eq!(serialization::to_g1::<Bn128Field>(parsed), point);
+        }
+
+        for point in g2s {
+            let json = serde_json::to_string(&parse_g2::<Bn128Field>(&point)).unwrap();
+            let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+            // two coordinates of two padded field elements each
+            for coordinate in value.as_array().unwrap() {
+                for c in coordinate.as_array().unwrap() {
+                    assert_eq!(c.as_str().unwrap().len(), 66);
+                }
+            }
+
+            let parsed: G2Affine = serde_json::from_str(&json).unwrap();
+            assert_eq!(serialization::to_g2::<Bn128Field>(parsed), point);
+        }
+    }
 
     #[test]
     fn verify() {
@@ -423,4 +1311,865 @@ mod tests {
 
         assert!(ans);
     }
+
+    #[test]
+    fn proof_meta() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let keypair = G16::setup(program.clone());
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+
+        let meta = ProofMeta::now::<Bn128Field>("g16");
+        assert_eq!(meta.curve, "bn128");
+        assert!(meta.created_at > 0);
+
+        let proof = G16::generate_proof(program.clone(), witness.clone(), keypair.pk.clone());
+        let json = serialize_proof(proof, Some(meta.clone()));
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["meta"]["scheme"], "g16");
+        assert_eq!(value["meta"]["created_at"], meta.created_at);
+        assert!(value["proof"]["a"].is_array());
+        assert_eq!(value["inputs"].as_array().unwrap().len(), 2);
+
+        let proof: Proof<ProofPoints> = serde_json::from_str(&json).unwrap();
+        assert_eq!(proof.meta, Some(meta));
+
+        let json = serialize_proof(G16::generate_proof(program, witness, keypair.pk), None);
+        assert!(!json.contains("\"meta\""));
+
+        let proof: Proof<ProofPoints> = serde_json::from_str(&json).unwrap();
+        assert_eq!(proof.meta, None);
+        assert!(<G16 as ProofSystem<Bn128Field>>::verify(keypair.vk, proof));
+    }
+
+    #[test]
+    fn string_inputs() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let keypair = G16::setup(program.clone());
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+        let json = serialize_proof(G16::generate_proof(program, witness, keypair.pk), None);
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let inputs: Vec<String> = serde_json::from_value(value["inputs"].clone()).unwrap();
+        let proof = BellmanProof::<Bn256>::read(
+            hex::decode(value["raw"].as_str().unwrap())
+                .unwrap()
+                .as_slice(),
+        )
+        .unwrap();
+        let vk = keypair.vk.into_bellman::<Bn128Field>();
+
+        let verify = |inputs: &[&str]| {
+            let inputs: Vec<String> = inputs.iter().map(|s| s.to_string()).collect();
+            G16::verify_with_string_inputs::<Bn128Field>(&vk, &proof, &inputs)
+        };
+
+        assert_eq!(
+            G16::verify_with_string_inputs::<Bn128Field>(&vk, &proof, &inputs),
+            Ok(true)
+        );
+        assert_eq!(verify(&["42", "42"]), Ok(true));
+        assert_eq!(verify(&["42", "43"]), Ok(false));
+        assert_eq!(
+            verify(&["42", "0xzz"]),
+            Err(InputError::Invalid(String::from("0xzz")))
+        );
+
+        // 42 plus the modulus would be reduced to 42 by `Field` arithmetic
+        let unreduced = (Bn128Field::modulus() + 42u32).to_str_radix(10);
+        assert_eq!(
+            verify(&["42", &unreduced]),
+            Err(InputError::OutOfRange(unreduced.clone()))
+        );
+        assert_eq!(
+            verify(&["42"]),
+            Err(InputError::WrongCount {
+                expected: 2,
+                got: 1
+            })
+        );
+    }
+
+    #[test]
+    fn gas_estimate() {
+        fn program_with_inputs(count: usize) -> Prog<Bn128Field> {
+            Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: (0..count).map(FlatVariable::new).collect(),
+                    returns: vec![],
+                    statements: (0..count)
+                        .map(|i| {
+                            Statement::Constraint(
+                                FlatVariable::new(i).into(),
+                                FlatVariable::new(i).into(),
+                            )
+                        })
+                        .collect(),
+                },
+                private: vec![false; count],
+            }
+        }
+
+        let vk = <G16 as ProofSystem<Bn128Field>>::setup(program_with_inputs(1)).vk;
+        let verifier =
+            <G16 as ProofSystem<Bn128Field>>::export_solidity_verifier(vk, SolidityAbi::V1);
+        assert!(verifier.contains(&format!(
+            "// Estimated gas cost of verifyTx with 1 public input: {}",
+            estimate_verification_gas(2).unwrap()
+        )));
+
+        let vk = <G16 as ProofSystem<Bn128Field>>::setup(program_with_inputs(5)).vk;
+        let verifier =
+            <G16 as ProofSystem<Bn128Field>>::export_solidity_verifier(vk, SolidityAbi::V2);
+        assert!(verifier.contains(&format!(
+            "// Estimated gas cost of verifyTx with 5 public inputs: {}",
+            estimate_verification_gas(6).unwrap()
+        )));
+
+        assert_eq!(
+            estimate_verification_gas(6).unwrap() - estimate_verification_gas(2).unwrap(),
+            4 * (EC_MUL_GAS + EC_ADD_GAS)
+        );
+        assert_eq!(estimate_verification_gas(0), None);
+    }
+
+    #[test]
+    fn circuit_id() {
+        let program: Prog<Bn128Field> = identity(true);
+
+        let expected = format!(
+            "bytes32 public constant CIRCUIT_ID = 0x{};",
+            hex::encode(program.structural_hash())
+        );
+
+        for abi in vec![SolidityAbi::V1, SolidityAbi::V2] {
+            let vk = <G16 as ProofSystem<Bn128Field>>::setup(program.clone()).vk;
+            let verifier = G16::export_solidity_verifier_with_circuit_id(vk, abi, &program);
+            assert!(verifier.contains(&format!("contract Verifier {{\n    {}", expected)));
+            assert_eq!(verifier.matches("CIRCUIT_ID").count(), 1);
+        }
+
+        let mut other = program.clone();
+        other.private = vec![false];
+        assert_ne!(other.structural_hash(), program.structural_hash());
+    }
+
+    #[test]
+    fn verify_with_wrong_vk() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let other_program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    (LinComb::from(FlatVariable::new(0)) + LinComb::one()).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            private: vec![false],
+        };
+
+        let keypair = <G16 as ProofSystem<Bn128Field>>::setup(program.clone());
+        let other_keypair = <G16 as ProofSystem<Bn128Field>>::setup(other_program);
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+
+        let proof = G16::generate_proof(program.clone(), witness.clone(), keypair.pk.clone());
+        assert_eq!(
+            G16::verify_checked::<Bn128Field>(keypair.vk, proof),
+            Ok(true)
+        );
+
+        let proof = G16::generate_proof(program, witness, keypair.pk);
+        match G16::verify_checked::<Bn128Field>(other_keypair.vk, proof) {
+            Err(VerificationError::VkMismatch { .. }) => {}
+            _ => panic!("expected a verification key mismatch"),
+        };
+    }
+
+    #[test]
+    fn wrong_input_count() {
+        let program = |count: usize| -> Prog<Bn128Field> {
+            Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: (0..count).map(FlatVariable::new).collect(),
+                    returns: vec![FlatVariable::public(0)],
+                    statements: vec![Statement::Constraint(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::public(0).into(),
+                    )],
+                },
+                private: vec![false; count],
+            }
+        };
+
+        let vk = <G16 as ProofSystem<Bn128Field>>::setup(program(1))
+            .vk
+            .into_bellman::<Bn128Field>();
+        assert_eq!(vk.ic.len(), 3);
+        assert_eq!(ic_for_input(&vk, 1), Some(vk.ic[2]));
+        assert_eq!(ic_for_input(&vk, 2), None);
+        assert_eq!(check_input_count(&vk, 2), Ok(()));
+
+        // the key of the program with one public argument, and a proof for the one with two
+        let keypair = <G16 as ProofSystem<Bn128Field>>::setup(program(1));
+        let other = program(2);
+        let other_keypair = <G16 as ProofSystem<Bn128Field>>::setup(other.clone());
+        let witness = Interpreter::default()
+            .execute(&other, &vec![Bn128Field::from(1), Bn128Field::from(2)])
+            .unwrap();
+        let proof = G16::generate_proof(other, witness, other_keypair.pk);
+
+        let res = G16::verify_checked::<Bn128Field>(keypair.vk, proof);
+        assert_eq!(
+            res,
+            Err(VerificationError::WrongInputCount {
+                expected: 2,
+                got: 3
+            })
+        );
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Verification key expects 2 public inputs but 3 were provided"
+        );
+    }
+
+    #[test]
+    fn serialize_any_curve() {
+        fn check<T: Field>() {
+            let program: Prog<T> = identity(false);
+
+            let witness = Interpreter::default()
+                .execute(&program, &vec![T::from(42)])
+                .unwrap();
+
+            let keypair = <G16 as ProofSystem<T>>::setup(program.clone());
+            let params = Parameters::<T::BellmanEngine>::read(keypair.pk.as_slice(), true).unwrap();
+            let proof = <G16 as ProofSystem<T>>::generate_proof(program, witness, keypair.pk);
+
+            // the generic serializers reproduce what setup and proof generation output
+            assert_eq!(
+                serde_json::to_string(&VerificationKey::from_bellman::<T>(&params.vk)).unwrap(),
+                serde_json::to_string(&keypair.vk).unwrap()
+            );
+
+            let raw =
+                BellmanProof::<T::BellmanEngine>::read(hex::decode(&proof.raw).unwrap().as_slice())
+                    .unwrap();
+            assert_eq!(
+                serde_json::to_string(&ProofPoints::from_bellman::<T>(&raw)).unwrap(),
+                serde_json::to_string(&proof.proof).unwrap()
+            );
+
+            assert!(<G16 as ProofSystem<T>>::verify(keypair.vk, proof));
+        }
+
+        check::<Bn128Field>();
+        check::<Bls12Field>();
+    }
+
+    #[test]
+    fn verify_explain() {
+        let (params, proof, inputs) = identity_proof(false);
+
+        assert_eq!(
+            G16::verify_explain::<Bn128Field>(&params.vk, &proof, &inputs),
+            VerifyResult::Ok
+        );
+
+        assert_eq!(
+            G16::verify_explain::<Bn128Field>(&params.vk, &proof, &inputs[..1]),
+            VerifyResult::WrongInputCount {
+                expected: 2,
+                got: 1
+            }
+        );
+
+        let tampered = BellmanProof {
+            a: proof.c,
+            ..proof
+        };
+        assert_eq!(
+            G16::verify_explain::<Bn128Field>(&params.vk, &tampered, &inputs),
+            VerifyResult::PairingFailed
+        );
+    }
+
+    #[test]
+    fn empty_ic() {
+        let (params, proof, _) = identity_proof(false);
+
+        // a deserialized key may have no point at all
+        let mut vk = params.vk;
+        vk.ic.clear();
+
+        // which no input count matches
+        assert_eq!(
+            check_input_count(&vk, 0),
+            Err(VerificationError::WrongInputCount {
+                expected: 0,
+                got: 0
+            })
+        );
+        assert_eq!(
+            G16::verify_explain::<Bn128Field>(&vk, &proof, &[]),
+            VerifyResult::WrongInputCount {
+                expected: 0,
+                got: 0
+            }
+        );
+        assert_eq!(
+            G16::verify_with_string_inputs::<Bn128Field>(&vk, &proof, &[]),
+            Err(InputError::WrongCount {
+                expected: 0,
+                got: 0
+            })
+        );
+        assert_eq!(
+            export_vyper_verifier(&vk),
+            Err(VerificationError::MalformedKey)
+        );
+        assert_eq!(
+            serialize_vk_snarkjs(&vk),
+            Err(VerificationError::MalformedKey)
+        );
+    }
+
+    #[test]
+    fn snarkjs_vk() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let vk = Computation::without_witness(program).setup().vk;
+
+        let json: serde_json::Value =
+            serde_json::from_str(&serialize_vk_snarkjs(&vk).unwrap()).unwrap();
+
+        assert_eq!(json["protocol"], "groth16");
+        assert_eq!(json["curve"], "bn128");
+        assert_eq!(json["nPublic"], 2);
+        assert_eq!(json["IC"].as_array().unwrap().len(), vk.ic.len());
+
+        let alpha = parse_g1::<Bn128Field>(&vk.alpha_g1);
+        assert_eq!(json["vk_alpha_1"][0], to_decimal(&alpha.0));
+        assert_eq!(json["vk_alpha_1"][2], "1");
+
+        // the real part of the first coordinate comes first
+        let beta = parse_g2::<Bn128Field>(&vk.beta_g2);
+        assert_eq!(json["vk_beta_2"][0][0], to_decimal(&(beta.0).1));
+        assert_eq!(json["vk_beta_2"][0][1], to_decimal(&(beta.0).0));
+        assert_eq!(json["vk_beta_2"][2], serde_json::json!(["1", "0"]));
+    }
+
+    #[test]
+    fn validate_points() {
+        use bellman::pairing::bn256::{Fq, Fq2, G1Affine, G2Affine};
+        use bellman::pairing::ff::SqrtField;
+
+        let program: Prog<Bn128Field> = identity(false);
+
+        let computation = Computation::new(program, &[Bn128Field::from(42)]).unwrap();
+        let params = computation.clone().setup();
+        let proof = computation.prove(&params);
+        assert_eq!(validate_proof_points(&proof), Ok(()));
+
+        // move `A` off the curve
+        let (x, mut y) = proof.a.into_xy_unchecked();
+        y.add_assign(&Fq::one());
+        let off_curve = BellmanProof {
+            a: G1Affine::from_xy_unchecked(x, y),
+            ..proof.clone()
+        };
+        assert_eq!(
+            validate_proof_points(&off_curve),
+            Err(PointError::NotOnCurve("A"))
+        );
+
+        // the twist has a large cofactor, so a point found by trying small `x` values is outside the subgroup
+        let (gx, gy) = G2Affine::one().into_xy_unchecked();
+        let mut b = gy;
+        b.square();
+        let mut gx3 = gx;
+        gx3.square();
+        gx3.mul_assign(&gx);
+        b.sub_assign(&gx3);
+
+        let outside = (1..)
+            .filter_map(|i| {
+                let x = Fq2 {
+                    c0: Fq::from_str(&i.to_string()).unwrap(),
+                    c1: Fq::zero(),
+                };
+                let mut rhs = x;
+                rhs.square();
+                rhs.mul_assign(&x);
+                rhs.add_assign(&b);
+                rhs.sqrt().map(|y| G2Affine::from_xy_checked(x, y).unwrap())
+            })
+            .next()
+            .unwrap();
+        let not_in_subgroup = BellmanProof {
+            b: outside,
+            ..proof.clone()
+        };
+        assert_eq!(
+            validate_proof_points(&not_in_subgroup),
+            Err(PointError::NotInSubgroup("B"))
+        );
+    }
+
+    #[test]
+    fn snarkjs_encodings() {
+        use crate::proof_system::bellman::SeededRandomness;
+
+        let program: Prog<Bn128Field> = identity(false);
+
+        let mut randomness = SeededRandomness([1, 2, 3, 4, 5, 6, 7, 8]);
+        let computation = Computation::new(program, &[Bn128Field::from(42)]).unwrap();
+        let params = computation.clone().setup_with(&mut randomness).unwrap();
+        let inputs = computation.public_inputs_values();
+        let proof = computation.prove_with(&params, &mut randomness);
+
+        // compares the leaves of two JSON documents of the same shape, one in each encoding
+        fn same_values(decimal: &serde_json::Value, hex: &serde_json::Value) {
+            match (decimal, hex) {
+                (serde_json::Value::String(decimal), serde_json::Value::String(hex)) => {
+                    assert!(hex.starts_with("0x"));
+                    assert_eq!(hex.len(), 66);
+                    assert_eq!(to_decimal(hex), *decimal);
+                }
+                (serde_json::Value::Array(decimal), serde_json::Value::Array(hex)) => {
+                    assert_eq!(decimal.len(), hex.len());
+                    for (d, h) in decimal.iter().zip(hex) {
+                        same_values(d, h);
+                    }
+                }
+                (serde_json::Value::Object(decimal), serde_json::Value::Object(hex)) => {
+                    assert_eq!(decimal.len(), hex.len());
+                    for (key, d) in decimal {
+                        match key.as_str() {
+                            "protocol" | "curve" | "nPublic" => assert_eq!(d, &hex[key]),
+                            _ => same_values(d, &hex[key]),
+                        }
+                    }
+                }
+                _ => panic!("Mismatched values {} and {}", decimal, hex),
+            }
+        }
+
+        let parse = |s: &str| serde_json::from_str::<serde_json::Value>(s).unwrap();
+
+        let (decimal_proof, decimal_public) =
+            serialize_proof_snarkjs_with_encoding(&proof, &inputs, Encoding::Decimal);
+        let (hex_proof, hex_public) =
+            serialize_proof_snarkjs_with_encoding(&proof, &inputs, Encoding::Hex);
+        assert_eq!(
+            (decimal_proof.clone(), decimal_public.clone()),
+            serialize_proof_snarkjs(&proof, &inputs)
+        );
+        same_values(&parse(&decimal_proof), &parse(&hex_proof));
+        same_values(&parse(&decimal_public), &parse(&hex_public));
+        assert_eq!(
+            parse(&hex_public),
+            serde_json::json!([format!("0x{:0>64}", "2a"), format!("0x{:0>64}", "2a")])
+        );
+        assert_eq!(parse(&hex_proof)["pi_a"][2], format!("0x{:0>64}", "1"));
+
+        let decimal_vk = serialize_vk_snarkjs_with_encoding(&params.vk, Encoding::Decimal).unwrap();
+        let hex_vk = serialize_vk_snarkjs_with_encoding(&params.vk, Encoding::Hex).unwrap();
+        assert_eq!(decimal_vk, serialize_vk_snarkjs(&params.vk).unwrap());
+        same_values(&parse(&decimal_vk), &parse(&hex_vk));
+    }
+
+    #[test]
+    fn snarkjs_proof() {
+        use bellman::pairing::bn256::{Fq, Fq2, G1Affine, G2Affine};
+        use bellman::pairing::ff::PrimeField;
+
+        let (params, proof, inputs) = identity_proof(false);
+
+        let (proof_json, public_json) = serialize_proof_snarkjs(&proof, &inputs);
+        let proof_json: serde_json::Value = serde_json::from_str(&proof_json).unwrap();
+        let public_json: Vec<String> = serde_json::from_str(&public_json).unwrap();
+
+        assert_eq!(proof_json["protocol"], "groth16");
+        assert_eq!(public_json, vec!["42", "42"]);
+
+        // read the proof back from its snarkjs representation and verify it natively
+        let fq = |v: &serde_json::Value| Fq::from_str(v.as_str().unwrap()).unwrap();
+        let g1 = |v: &serde_json::Value| {
+            assert_eq!(v[2], "1");
+            G1Affine::from_xy_checked(fq(&v[0]), fq(&v[1])).unwrap()
+        };
+        let fq2 = |v: &serde_json::Value| Fq2 {
+            c0: fq(&v[0]),
+            c1: fq(&v[1]),
+        };
+        let g2 = |v: &serde_json::Value| {
+            assert_eq!(v[2], serde_json::json!(["1", "0"]));
+            G2Affine::from_xy_checked(fq2(&v[0]), fq2(&v[1])).unwrap()
+        };
+
+        let read = BellmanProof {
+            a: g1(&proof_json["pi_a"]),
+            b: g2(&proof_json["pi_b"]),
+            c: g1(&proof_json["pi_c"]),
+        };
+        assert!(proofs_equal(&read, &proof));
+
+        let inputs: Vec<Fr> = public_json
+            .iter()
+            .map(|i| Fr::from_str(i).unwrap())
+            .collect();
+        let pvk = prepare_verifying_key(&params.vk);
+        assert!(verify_proof(&pvk, &read, &inputs).unwrap());
+    }
+
+    #[test]
+    fn vyper_verifier() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0), FlatVariable::new(1)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    (LinComb::from(FlatVariable::new(0)) + LinComb::from(FlatVariable::new(1)))
+                        .into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            private: vec![false, false],
+        };
+
+        let params = Computation::without_witness(program).setup();
+        let verifier = export_vyper_verifier(&params.vk).unwrap();
+
+        let vk = VerificationKey::from_bellman::<Bn128Field>(&params.vk);
+        let coordinates = vec![&vk.alpha.0, &vk.alpha.1]
+            .into_iter()
+            .chain(
+                vec![&vk.beta, &vk.gamma, &vk.delta]
+                    .into_iter()
+                    .flat_map(|g2| vec![&(g2.0).0, &(g2.0).1, &(g2.1).0, &(g2.1).1]),
+            )
+            .chain(vk.gamma_abc.iter().flat_map(|g1| vec![&g1.0, &g1.1]));
+        for coordinate in coordinates {
+            assert!(verifier.contains(&format!("convert({}, uint256)", coordinate)));
+        }
+
+        // the first entry is not associated with an input, followed by the two arguments and the return value
+        assert!(verifier.contains("gamma_abc: G1Point[4] = ["));
+        // `alpha` and the entries of `gamma_abc`
+        assert_eq!(verifier.matches("G1Point({x: convert(").count(), 1 + 4);
+        assert!(verifier.contains(", input: uint256[3]) -> bool:"));
+        assert!(verifier.contains("for i in range(3):"));
+        assert!(!verifier.contains("<%"));
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::new(0).into(),
+                )],
+            },
+            private: vec![true],
+        };
+
+        let params = Computation::without_witness(program).setup();
+        let verifier = export_vyper_verifier(&params.vk).unwrap();
+        assert!(verifier.contains("gamma_abc: G1Point[1] = ["));
+        assert!(verifier.contains("c: uint256[2]) -> bool:"));
+        assert!(!verifier.contains("for i in range"));
+    }
+
+    #[test]
+    fn base64_roundtrip() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let computation = Computation::new(program, &[Bn128Field::from(42)]).unwrap();
+        let params = computation.clone().setup();
+        let proof = computation.prove(&params);
+
+        let encoded = proof_to_base64(&proof);
+        assert!(proofs_equal(&proof_from_base64(&encoded).unwrap(), &proof));
+
+        let encoded = vk_to_base64(&params.vk);
+        assert_eq!(vk_to_base64(&vk_from_base64(&encoded).unwrap()), encoded);
+
+        assert!(proof_from_base64("not base64").is_err());
+        assert!(vk_from_base64(&encoded[..encoded.len() - 4]).is_err());
+    }
+
+    #[test]
+    fn vk_size() {
+        let mut builder = ProgBuilder::new();
+        let inputs: Vec<_> = (0..5).map(|_| builder.public_input()).collect();
+        let sum = builder.variable();
+        builder.constrain(
+            inputs
+                .into_iter()
+                .fold(LinComb::zero(), |acc, i| acc + i.into()),
+            sum,
+        );
+        builder.output(sum);
+        let program: Prog<Bn128Field> = builder.build().unwrap();
+
+        let vk = Computation::without_witness(program)
+            .verifying_key_only(&mut ThreadRandomness)
+            .unwrap();
+        assert_eq!(vk.ic.len(), 7);
+
+        let mut bytes = vec![];
+        write_pvk::<Bn128Field, _>(&vk, &mut bytes).unwrap();
+        assert_eq!(vk_size_bytes(&vk), bytes.len());
+        assert_eq!(vk_size_bytes(&vk), 3 * 64 + 3 * 128 + 4 + 7 * 64);
+    }
+
+    #[test]
+    fn inputs_binary() {
+        let inputs: Vec<Fr> = vec![
+            Bn128Field::from(0),
+            Bn128Field::from(1),
+            Bn128Field::from(42),
+            Bn128Field::from(-1),
+        ]
+        .into_iter()
+        .map(|i| i.into_bellman())
+        .collect();
+
+        let mut bytes = vec![];
+        write_inputs::<Bn128Field, _>(&inputs, &mut bytes).unwrap();
+        assert_eq!(bytes.len(), 4 + 4 * 32);
+        assert_eq!(&bytes[..4], &[0, 0, 0, 4]);
+
+        assert_eq!(read_inputs::<Bn128Field, _>(&bytes[..]).unwrap(), inputs);
+
+        // the modulus itself is not a valid element
+        let mut modulus = bytes[..4 + 32].to_vec();
+        modulus[3] = 1;
+        Fr::char().write_be(&mut modulus[4..]).unwrap();
+        assert_eq!(
+            read_inputs::<Bn128Field, _>(&modulus[..])
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        // truncated input
+        assert!(read_inputs::<Bn128Field, _>(&bytes[..40]).is_err());
+    }
+
+    #[test]
+    fn inputs_hash() {
+        let inputs: Vec<Fr> = vec![1, 2, 42]
+            .into_iter()
+            .map(|i| Bn128Field::from(i).into_bellman())
+            .collect();
+        assert_eq!(
+            hex::encode(public_inputs_hash(&inputs)),
+            "cd8da4d0454061ea75145a5a7e999c8eba30b4456b18b2ebfa3c5783ee7cc2d7"
+        );
+
+        // `p - 1`, to check that inputs are hashed in their canonical form
+        assert_eq!(
+            hex::encode(public_inputs_hash(&[Bn128Field::from(-1).into_bellman()])),
+            "985b362850779535b462b01f4f8e43f0352b2a2f9fbd3852991123568f6cb981"
+        );
+    }
+
+    #[test]
+    fn rerandomize() {
+        let (params, proof, inputs) = identity_proof(true);
+
+        let rng = &mut crate::proof_system::bellman::rand::thread_rng();
+        let rerandomized = rerandomize_proof(&proof, &params.vk, rng);
+
+        assert!(proofs_equal(&proof, &proof));
+        assert!(!proofs_equal(&proof, &rerandomized));
+
+        let pvk = prepare_verifying_key(&params.vk);
+        assert!(verify_proof(&pvk, &rerandomized, &inputs).unwrap());
+    }
+
+    #[test]
+    fn batch() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let params = Computation::without_witness(program.clone()).setup();
+
+        let mut items: Vec<_> = vec![1, 2, 3]
+            .into_iter()
+            .map(|i| {
+                let witness = Interpreter::default()
+                    .execute(&program, &vec![Bn128Field::from(i)])
+                    .unwrap();
+                let computation = Computation::with_witness(program.clone(), witness);
+                let inputs = computation.public_inputs_values();
+                (computation.prove(&params), inputs)
+            })
+            .collect();
+
+        // claim the third proof was generated for other inputs
+        items[2].1 = vec![Bn128Field::from(4).into_bellman(); 2];
+
+        assert_eq!(verify_batch(&params.vk, &items), vec![true, true, false]);
+    }
+
+    #[test]
+    fn contribution() {
+        let computation = Computation::new(identity(true), &[Bn128Field::from(42)]).unwrap();
+
+        let initial = computation.clone().setup();
+        let (first, first_hash) = contribute(initial.clone(), b"first");
+        let (second, second_hash) = contribute(first.clone(), b"second");
+
+        assert_ne!(first.vk.delta_g2, initial.vk.delta_g2);
+        assert_ne!(first_hash, second_hash);
+
+        let inputs = computation.public_inputs_values();
+        let proof = computation.prove(&second);
+
+        let pvk = prepare_verifying_key(&second.vk);
+        assert!(verify_proof(&pvk, &proof, &inputs).unwrap());
+    }
+
+    #[test]
+    fn verify_contribution() {
+        let program: Prog<Bn128Field> = identity(true);
+
+        let before = Computation::without_witness(program).setup();
+        let (after, hash) = contribute(before.clone(), b"entropy");
+
+        assert!(super::verify_contribution(&before, &after, &hash));
+        assert!(!super::verify_contribution(
+            &before,
+            &after,
+            &ContributionHash([0; 32])
+        ));
+
+        // a contributor leaving `l` untouched, even with a matching hash
+        let mut tampered = after.clone();
+        tampered.l = before.l.clone();
+        let tampered_hash = ContributionHash::new(&before, &tampered);
+        assert!(!super::verify_contribution(
+            &before,
+            &tampered,
+            &tampered_hash
+        ));
+
+        // a contributor changing `alpha`
+        let mut tampered = after.clone();
+        tampered.vk.alpha_g1 = tampered.vk.beta_g1;
+        let tampered_hash = ContributionHash::new(&before, &tampered);
+        assert!(!super::verify_contribution(
+            &before,
+            &tampered,
+            &tampered_hash
+        ));
+    }
+
+    #[test]
+    fn cached_pvk() {
+        let (params, proof, inputs) = identity_proof(false);
+
+        let mut buf = vec![];
+        write_pvk::<Bn128Field, _>(&params.vk, &mut buf).unwrap();
+        let cached = read_pvk::<Bn128Field, _>(buf.as_slice()).unwrap();
+        let fresh = prepare_verifying_key(&params.vk);
+
+        assert!(verify_proof(&fresh, &proof, &inputs).unwrap());
+        assert!(verify_proof(&cached, &proof, &inputs).unwrap());
+
+        let wrong_inputs = vec![inputs[0], Bn128Field::from(41).into_bellman()];
+        assert!(!verify_proof(&fresh, &proof, &wrong_inputs).unwrap());
+        assert!(!verify_proof(&cached, &proof, &wrong_inputs).unwrap());
+    }
+
+    #[test]
+    fn ethereum_layout() {
+        use bellman::pairing::ff::PrimeField;
+
+        let proof = BellmanProof::<Bn256> {
+            a: <Bn256 as Engine>::G1Affine::one(),
+            b: <Bn256 as Engine>::G2Affine::one(),
+            c: <Bn256 as Engine>::G1Affine::one(),
+        };
+
+        let args = serialize_proof_ethereum(&proof, &[Fr::from_str("5").unwrap()]);
+
+        let g1 = [
+            String::from("0x0000000000000000000000000000000000000000000000000000000000000001"),
+            String::from("0x0000000000000000000000000000000000000000000000000000000000000002"),
+        ];
+
+        assert_eq!(
+            args,
+            EthProofArgs {
+                a: g1.clone(),
+                // the G2 generator, with the imaginary part of each coordinate first
+                b: [
+                    [
+                        String::from(
+                            "0x198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"
+                        ),
+                        String::from(
+                            "0x1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed"
+                        ),
+                    ],
+                    [
+                        String::from(
+                            "0x090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b"
+                        ),
+                        String::from(
+                            "0x12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+                        ),
+                    ],
+                ],
+                c: g1,
+                inputs: vec![String::from(
+                    "0x0000000000000000000000000000000000000000000000000000000000000005"
+                )],
+            }
+        );
+    }
+
+    #[test]
+    fn through_trait() {
+        // drives a backend using only the `ProofSystem` trait, serializing keys and proofs as the CLI does
+        fn run<T: Field, S: ProofSystem<T>>() {
+            let program: Prog<T> = identity(true);
+
+            let witness = Interpreter::default()
+                .execute(&program, &vec![T::from(42)])
+                .unwrap();
+
+            let keypair = S::setup(program.clone());
+            let vk = S::serialize_vk(&keypair.vk);
+
+            let proof = S::generate_proof(program, witness, keypair.pk);
+            let proof = S::serialize_proof(&proof);
+
+            assert!(S::verify(
+                serde_json::from_str(&vk).unwrap(),
+                serde_json::from_str(&proof).unwrap()
+            ));
+        }
+
+        run::<Bn128Field, G16>();
+        run::<Bls12Field, G16>();
+    }
 }
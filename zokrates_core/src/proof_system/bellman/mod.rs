@@ -1,34 +1,184 @@
+pub mod aggregation;
 pub mod groth16;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 extern crate rand;
 
-use crate::ir::{CanonicalLinComb, Prog, Statement, Witness};
+use crate::ir::{self, CanonicalLinComb, Prog, Statement, Witness};
 use bellman::groth16::Proof;
 use bellman::groth16::{
     create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
-    Parameters,
+    Parameters, VerifyingKey,
 };
 use bellman::pairing::ff::ScalarEngine;
 use bellman::{Circuit, ConstraintSystem, LinearCombination, SynthesisError, Variable};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use zokrates_field::Field;
 
-use self::rand::ChaChaRng;
+#[cfg(not(target_arch = "wasm32"))]
+use self::rand::ThreadRng;
+use self::rand::{ChaChaRng, Rng, SeedableRng};
 use crate::flat_absy::FlatVariable;
 
 pub use self::parse::*;
 
+/// The error returned when reading values from a computation which has no witness
+#[derive(Debug, PartialEq)]
+pub struct NoWitness;
+
+impl fmt::Display for NoWitness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Computation has no witness")
+    }
+}
+
+/// The error returned when the public inputs of a program cannot be extracted from a witness
+#[derive(Debug, PartialEq)]
+pub enum PublicInputsError {
+    /// The witness does not hold all the public inputs of the program
+    LengthMismatch { expected: usize, found: usize },
+    /// `~one` is an argument of the program, which only happens for malformed programs
+    OneAsArgument,
+}
+
+impl fmt::Display for PublicInputsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PublicInputsError::LengthMismatch { expected, found } => write!(
+                f,
+                "Program has {} public input{} but the witness holds {}",
+                expected,
+                if expected == 1 { "" } else { "s" },
+                found
+            ),
+            PublicInputsError::OneAsArgument => write!(
+                f,
+                "{} is an implicit input and cannot be an argument of the program",
+                FlatVariable::one()
+            ),
+        }
+    }
+}
+
+/// The error returned when proving a program with parameters generated for another program
+#[derive(Debug, PartialEq)]
+pub struct CircuitMismatch {
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for CircuitMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Parameters were generated for circuit {} but circuit {} is being proven",
+            self.expected, self.found
+        )
+    }
+}
+
+/// The error returned when a program has more constraints than a computation accepts
+#[derive(Debug, PartialEq)]
+pub struct CircuitTooLarge {
+    pub limit: usize,
+    pub actual: usize,
+}
+
+impl fmt::Display for CircuitTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Program has {} constraints, more than the limit of {}",
+            self.actual, self.limit
+        )
+    }
+}
+
+/// The default limit on the number of constraints of a computation. Larger circuits would not fit in the evaluation
+/// domains of the bn128 scalar field anyway.
+pub const DEFAULT_MAX_CONSTRAINTS: usize = 1 << 28;
+
+/// Setup parameters along with the structural hash of the program they were generated for
+pub struct CircuitParameters<E: bellman::pairing::Engine> {
+    pub parameters: Parameters<E>,
+    pub circuit_hash: [u8; 32],
+}
+
+/// A source of randomness for the setup and proving phases, so that callers choose between a secure generator and
+/// a reproducible one
+pub trait Randomness {
+    type Rng: Rng;
+
+    /// Returns the generator to use for the next setup or proof
+    fn rng(&mut self) -> Self::Rng;
+}
+
+/// Randomness from `thread_rng`, seeded by the operating system. This is what `setup` and `prove` use. On wasm,
+/// where `thread_rng` is not available, the generator is seeded with `getrandom` instead.
+#[cfg(any(not(target_arch = "wasm32"), feature = "wasm"))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ThreadRandomness;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Randomness for ThreadRandomness {
+    type Rng = ThreadRng;
+
+    fn rng(&mut self) -> ThreadRng {
+        self::rand::thread_rng()
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+impl Randomness for ThreadRandomness {
+    type Rng = ChaChaRng;
+
+    fn rng(&mut self) -> ChaChaRng {
+        wasm::rng().unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+/// Deterministic randomness from a fixed seed, which yields the same generator each time. Only meant for tests.
+#[derive(Debug, Clone)]
+pub struct SeededRandomness(pub [u32; 8]);
+
+impl Randomness for SeededRandomness {
+    type Rng = ChaChaRng;
+
+    fn rng(&mut self) -> ChaChaRng {
+        ChaChaRng::from_seed(&self.0)
+    }
+}
+
+/// Returns a generator seeded with `seed`, read as eight little-endian words
+pub(crate) fn rng_from_seed_bytes(seed: &[u8; 32]) -> ChaChaRng {
+    let words: Vec<u32> = seed
+        .chunks(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+    ChaChaRng::from_seed(&words[..])
+}
+
 #[derive(Clone)]
 pub struct Computation<T> {
     program: Prog<T>,
     witness: Option<Witness<T>>,
+    max_constraints: usize,
 }
 
 impl<T: Field> Computation<T> {
+    /// Executes `program` on `inputs`, returning a computation holding the resulting witness
+    pub fn new(program: Prog<T>, inputs: &[T]) -> Result<Self, ir::Error> {
+        let witness = ir::Interpreter::default().execute(&program, &inputs.to_vec())?;
+        Ok(Computation::with_witness(program, witness))
+    }
+
     pub fn with_witness(program: Prog<T>, witness: Witness<T>) -> Self {
         Computation {
             program,
             witness: Some(witness),
+            max_constraints: DEFAULT_MAX_CONSTRAINTS,
         }
     }
 
@@ -36,61 +186,316 @@ impl<T: Field> Computation<T> {
         Computation {
             program,
             witness: None,
+            max_constraints: DEFAULT_MAX_CONSTRAINTS,
         }
     }
+
+    /// Sets the largest number of constraints this computation accepts, `DEFAULT_MAX_CONSTRAINTS` by default.
+    /// Setup and proving fail before synthesizing a program with more constraints, so that services accepting
+    /// programs from untrusted sources do not run out of memory.
+    pub fn with_max_constraints(self, max_constraints: usize) -> Self {
+        Computation {
+            max_constraints,
+            ..self
+        }
+    }
+
+    /// Checks that the program of this computation does not have more constraints than the limit
+    pub fn check_size(&self) -> Result<(), CircuitTooLarge> {
+        let actual = self.program.constraint_count();
+
+        match actual <= self.max_constraints {
+            true => Ok(()),
+            false => Err(CircuitTooLarge {
+                limit: self.max_constraints,
+                actual,
+            }),
+        }
+    }
+
+    /// Drops the witness, keeping only what a verifier needs
+    pub fn into_verifier(self) -> Self {
+        Computation {
+            witness: None,
+            ..self
+        }
+    }
+}
+
+/// The label of the wire for `v`, taken from `names` if present, `default` otherwise
+fn wire_name<F: FnOnce() -> String>(
+    names: Option<&BTreeMap<FlatVariable, String>>,
+    v: &FlatVariable,
+    default: F,
+) -> String {
+    names
+        .and_then(|names| names.get(v).cloned())
+        .unwrap_or_else(default)
+}
+
+/// Converts a coefficient to the bellman scalar field, rejecting values which are not reduced modulo the field prime
+fn bellman_coefficient<T: Field>(
+    v: T,
+) -> Result<<T::BellmanEngine as ScalarEngine>::Fr, SynthesisError> {
+    let reduced = v.to_biguint() <= T::max_value().to_biguint();
+    debug_assert!(reduced, "Coefficient {} is not reduced", v);
+    match reduced {
+        true => Ok(v.into_bellman()),
+        false => Err(SynthesisError::Unsatisfiable),
+    }
+}
+
+/// A mapping from IR variables to bellman variables
+trait Symbols: Default + Extend<(FlatVariable, Variable)> {
+    fn insert(&mut self, k: FlatVariable, v: Variable) -> Option<Variable>;
+
+    /// Returns the variable for `k`, allocating it with `f` if there is none. Nothing is inserted if `f` fails.
+    fn get_or_try_insert_with<F: FnOnce() -> Result<Variable, SynthesisError>>(
+        &mut self,
+        k: FlatVariable,
+        f: F,
+    ) -> Result<Variable, SynthesisError>;
+}
+
+impl Symbols for BTreeMap<FlatVariable, Variable> {
+    fn insert(&mut self, k: FlatVariable, v: Variable) -> Option<Variable> {
+        BTreeMap::insert(self, k, v)
+    }
+
+    fn get_or_try_insert_with<F: FnOnce() -> Result<Variable, SynthesisError>>(
+        &mut self,
+        k: FlatVariable,
+        f: F,
+    ) -> Result<Variable, SynthesisError> {
+        match self.entry(k) {
+            std::collections::btree_map::Entry::Occupied(e) => Ok(*e.get()),
+            std::collections::btree_map::Entry::Vacant(e) => Ok(*e.insert(f()?)),
+        }
+    }
+}
+
+impl Symbols for HashMap<FlatVariable, Variable> {
+    fn insert(&mut self, k: FlatVariable, v: Variable) -> Option<Variable> {
+        HashMap::insert(self, k, v)
+    }
+
+    fn get_or_try_insert_with<F: FnOnce() -> Result<Variable, SynthesisError>>(
+        &mut self,
+        k: FlatVariable,
+        f: F,
+    ) -> Result<Variable, SynthesisError> {
+        match self.entry(k) {
+            std::collections::hash_map::Entry::Occupied(e) => Ok(*e.get()),
+            std::collections::hash_map::Entry::Vacant(e) => Ok(*e.insert(f()?)),
+        }
+    }
+}
+
+// the `hashmap` feature trades the ordering of the symbol table for faster lookups in large circuits
+#[cfg(not(feature = "hashmap"))]
+type DefaultSymbols = BTreeMap<FlatVariable, Variable>;
+#[cfg(feature = "hashmap")]
+type DefaultSymbols = HashMap<FlatVariable, Variable>;
+
+/// A constraint system which forwards to `cs`, counting the constraints enforced so far
+struct Counting<'a, CS> {
+    cs: &'a mut CS,
+    enforced: usize,
 }
 
-fn bellman_combination<T: Field, CS: ConstraintSystem<T::BellmanEngine>>(
+impl<'a, E: bellman::pairing::Engine, CS: ConstraintSystem<E>> ConstraintSystem<E>
+    for Counting<'a, CS>
+{
+    type Root = Self;
+
+    fn one() -> Variable {
+        CS::one()
+    }
+
+    fn alloc<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.cs.alloc(annotation, f)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.cs.alloc_input(annotation, f)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+    {
+        self.cs.enforce(annotation, a, b, c);
+        self.enforced += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.cs.push_namespace(name_fn)
+    }
+
+    fn pop_namespace(&mut self) {
+        self.cs.pop_namespace()
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+fn bellman_combination<T: Field, CS: ConstraintSystem<T::BellmanEngine>, S: Symbols>(
     l: CanonicalLinComb<T>,
     cs: &mut CS,
-    symbols: &mut BTreeMap<FlatVariable, Variable>,
+    symbols: &mut S,
     witness: &mut Witness<T>,
-) -> LinearCombination<T::BellmanEngine> {
+    names: Option<&BTreeMap<FlatVariable, String>>,
+) -> Result<LinearCombination<T::BellmanEngine>, SynthesisError> {
     l.0.into_iter()
         .map(|(k, v)| {
-            (
-                v.into_bellman(),
-                symbols
-                    .entry(k)
-                    .or_insert_with(|| {
-                        match k.is_output() {
-                            true => cs.alloc_input(
-                                || format!("{}", k),
-                                || {
-                                    Ok(witness
-                                        .0
-                                        .remove(&k)
-                                        .ok_or(SynthesisError::AssignmentMissing)?
-                                        .into_bellman())
-                                },
-                            ),
-                            false => cs.alloc(
-                                || format!("{}", k),
-                                || {
-                                    Ok(witness
-                                        .0
-                                        .remove(&k)
-                                        .ok_or(SynthesisError::AssignmentMissing)?
-                                        .into_bellman())
-                                },
-                            ),
-                        }
-                        .unwrap()
-                    })
-                    .clone(),
-            )
+            Ok((
+                bellman_coefficient(v)?,
+                symbols.get_or_try_insert_with(k, || match k.is_output() {
+                    true => cs.alloc_input(
+                        || wire_name(names, &k, || format!("{}", k)),
+                        || {
+                            Ok(witness
+                                .0
+                                .remove(&k)
+                                .ok_or(SynthesisError::AssignmentMissing)?
+                                .into_bellman())
+                        },
+                    ),
+                    false => cs.alloc(
+                        || wire_name(names, &k, || format!("{}", k)),
+                        || {
+                            Ok(witness
+                                .0
+                                .remove(&k)
+                                .ok_or(SynthesisError::AssignmentMissing)?
+                                .into_bellman())
+                        },
+                    ),
+                })?,
+            ))
         })
-        .fold(LinearCombination::zero(), |acc, e| acc + e)
+        .try_fold(LinearCombination::zero(), |acc, e| e.map(|e| acc + e))
 }
 
 impl<T: Field> Prog<T> {
+    /// Estimates the size in bytes of the proving key written by `Parameters::write` for this program, without
+    /// running the setup. With `n` public wires including `~one`, `m` private wires, `c` constraints and `N` the
+    /// smallest power of two at least `c + n`, the key holds:
+    ///
+    /// * the verification key, of 3 G1 points, 3 G2 points and `n` G1 points
+    /// * `N - 1` G1 points in the `H` query and `m` in the `L` query
+    /// * a G1 point in the `A` query for each public wire and each private wire used on the left of a product
+    /// * a G1 and a G2 point in the `B` queries for each wire used on the right of a product
+    ///
+    /// Each query is prefixed with its length on 4 bytes, and points are uncompressed. bellman drops the points of
+    /// the `A` and `B` queries which are zero, which only happens with negligible probability, so the estimate is
+    /// an upper bound which is reached in practice.
+    pub fn estimated_proving_key_bytes(&self) -> usize {
+        use bellman::pairing::{CurveAffine, EncodedPoint, Engine};
+        use std::collections::BTreeSet;
+
+        let g1 = <<T::BellmanEngine as Engine>::G1Affine as CurveAffine>::Uncompressed::size();
+        let g2 = <<T::BellmanEngine as Engine>::G2Affine as CurveAffine>::Uncompressed::size();
+
+        let public: BTreeSet<FlatVariable> = std::iter::once(FlatVariable::one())
+            .chain(
+                self.input_signature()
+                    .into_iter()
+                    .filter(|(_, private)| !private)
+                    .map(|(v, _)| v),
+            )
+            .chain(self.main.returns.iter().cloned())
+            .collect();
+
+        // private arguments are allocated even if no constraint uses them
+        let mut private: BTreeSet<FlatVariable> = self.private_variables().into_iter().collect();
+        let mut left = public.clone();
+        let mut right = BTreeSet::new();
+
+        for (quad, lin) in self.constraints() {
+            let a = quad.left.clone().into_canonical();
+            let b = quad.right.clone().into_canonical();
+            let c = lin.clone().into_canonical();
+
+            left.extend(a.0.keys().cloned());
+            right.extend(b.0.keys().cloned());
+            private.extend(
+                a.0.keys()
+                    .chain(b.0.keys())
+                    .chain(c.0.keys())
+                    .filter(|v| !public.contains(v))
+                    .cloned(),
+            );
+        }
+
+        let domain_size = (self.constraint_count() + public.len()).next_power_of_two();
+
+        let vk = 3 * g1 + 3 * g2 + 4 + public.len() * g1;
+        let queries = 5 * 4
+            + (domain_size - 1 + private.len() + left.len() + right.len()) * g1
+            + right.len() * g2;
+
+        vk + queries
+    }
+
+    /// Synthesizes this program into `cs`. Wires are labelled using `names` when provided,
+    /// and with numeric labels otherwise.
+    ///
+    /// The program is not checked with `validate`, which callers may run beforehand.
     pub fn synthesize<CS: ConstraintSystem<T::BellmanEngine>>(
         self,
         cs: &mut CS,
         witness: Option<Witness<T>>,
+        names: Option<&BTreeMap<FlatVariable, String>>,
+    ) -> Result<(), SynthesisError> {
+        self.synthesize_with::<CS, DefaultSymbols>(cs, witness, names)
+    }
+
+    /// Synthesizes this program into `cs` like `synthesize`, returning the number of constraints enforced. On
+    /// failure, the error comes with the number of constraints enforced before it, which is also the index of the
+    /// failing constraint among the constraints of the program.
+    pub fn synthesize_counted<CS: ConstraintSystem<T::BellmanEngine>>(
+        self,
+        cs: &mut CS,
+        witness: Option<Witness<T>>,
+        names: Option<&BTreeMap<FlatVariable, String>>,
+    ) -> Result<usize, (usize, SynthesisError)> {
+        let mut counting = Counting { cs, enforced: 0 };
+
+        match self.synthesize(&mut counting, witness, names) {
+            Ok(()) => Ok(counting.enforced),
+            Err(e) => Err((counting.enforced, e)),
+        }
+    }
+
+    fn synthesize_with<CS: ConstraintSystem<T::BellmanEngine>, S: Symbols>(
+        self,
+        cs: &mut CS,
+        witness: Option<Witness<T>>,
+        names: Option<&BTreeMap<FlatVariable, String>>,
     ) -> Result<(), SynthesisError> {
         // mapping from IR variables
-        let mut symbols = BTreeMap::new();
+        let mut symbols = S::default();
 
         let mut witness = witness.unwrap_or(Witness::empty());
 
@@ -105,7 +510,7 @@ impl<T: Field> Prog<T> {
                 .map(|(index, (var, private))| {
                     let wire = match private {
                         true => cs.alloc(
-                            || format!("PRIVATE_INPUT_{}", index),
+                            || wire_name(names, var, || format!("PRIVATE_INPUT_{}", index)),
                             || {
                                 Ok(witness
                                     .0
@@ -115,7 +520,7 @@ impl<T: Field> Prog<T> {
                             },
                         ),
                         false => cs.alloc_input(
-                            || format!("PUBLIC_INPUT_{}", index),
+                            || wire_name(names, var, || format!("PUBLIC_INPUT_{}", index)),
                             || {
                                 Ok(witness
                                     .0
@@ -140,15 +545,22 @@ impl<T: Field> Prog<T> {
                         cs,
                         &mut symbols,
                         &mut witness,
-                    );
+                        names,
+                    )?;
                     let b = &bellman_combination(
                         quad.right.into_canonical(),
                         cs,
                         &mut symbols,
                         &mut witness,
-                    );
-                    let c =
-                        &bellman_combination(lin.into_canonical(), cs, &mut symbols, &mut witness);
+                        names,
+                    )?;
+                    let c = &bellman_combination(
+                        lin.into_canonical(),
+                        cs,
+                        &mut symbols,
+                        &mut witness,
+                        names,
+                    )?;
 
                     cs.enforce(|| "Constraint", |lc| lc + a, |lc| lc + b, |lc| lc + c);
                 }
@@ -162,39 +574,151 @@ impl<T: Field> Prog<T> {
 
 impl<T: Field> Computation<T> {
     pub fn prove(self, params: &Parameters<T::BellmanEngine>) -> Proof<T::BellmanEngine> {
-        let rng = &mut ChaChaRng::new_unseeded();
-
-        let proof = create_random_proof(self.clone(), params, rng).unwrap();
+        self.prove_with(params, &mut ThreadRandomness)
+    }
 
-        let pvk = prepare_verifying_key(&params.vk);
+    /// Generates a proof like `prove`, drawing the blinding factors from `randomness`
+    pub fn prove_with<R: Randomness>(
+        self,
+        params: &Parameters<T::BellmanEngine>,
+        randomness: &mut R,
+    ) -> Proof<T::BellmanEngine> {
+        self.prove_with_rng(params, &mut randomness.rng())
+    }
 
-        // extract public inputs
+    fn prove_with_rng<R: Rng>(
+        self,
+        params: &Parameters<T::BellmanEngine>,
+        rng: &mut R,
+    ) -> Proof<T::BellmanEngine> {
+        // extract public inputs before the computation is consumed by the prover, to avoid cloning it
         let public_inputs = self.public_inputs_values();
 
+        let proof = create_random_proof(self, params, rng).unwrap();
+
+        let pvk = prepare_verifying_key(&params.vk);
+
         assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
 
         proof
     }
 
+    /// Runs the setup phase like `setup`, recording the structural hash of the program so that `prove_checked`
+    /// can detect parameters used with another program
+    pub fn setup_with_circuit_hash(self) -> CircuitParameters<T::BellmanEngine> {
+        let circuit_hash = self.program.structural_hash();
+        CircuitParameters {
+            parameters: self.setup(),
+            circuit_hash,
+        }
+    }
+
+    /// Generates a proof like `prove`, failing if `params` were generated for another program
+    pub fn prove_checked(
+        self,
+        params: &CircuitParameters<T::BellmanEngine>,
+    ) -> Result<Proof<T::BellmanEngine>, CircuitMismatch> {
+        let found = self.program.structural_hash();
+
+        match found == params.circuit_hash {
+            true => Ok(self.prove(&params.parameters)),
+            false => Err(CircuitMismatch {
+                expected: hex::encode(params.circuit_hash),
+                found: hex::encode(found),
+            }),
+        }
+    }
+
+    /// Returns whether a proof generated from this computation would verify, by checking the witness against the
+    /// constraints of the program instead of running the prover. Fails if the computation has no witness.
+    pub fn would_verify(&self) -> Result<bool, SynthesisError> {
+        self.witness
+            .as_ref()
+            .map(|witness| self.program.check_constraints(witness).is_ok())
+            .ok_or(SynthesisError::AssignmentMissing)
+    }
+
     pub fn public_inputs_values(&self) -> Vec<<T::BellmanEngine as ScalarEngine>::Fr> {
-        self.program
-            .main
-            .arguments
-            .clone()
-            .iter()
-            .zip(self.program.private.clone())
-            .filter(|(_, p)| !p)
-            .map(|(a, _)| a)
-            .map(|v| self.witness.clone().unwrap().0.get(v).unwrap().clone())
-            .chain(self.witness.clone().unwrap().return_values())
-            .map(|v| v.clone().into_bellman())
-            .collect()
+        // a program without public inputs nor return values has no public inputs to read from the witness
+        if self.program.private.iter().all(|p| *p) && self.program.main.returns.is_empty() {
+            return vec![];
+        }
+
+        self.inputs().unwrap()
+    }
+
+    /// Extracts the public inputs from the witness of this computation, failing if it has none
+    pub fn inputs(&self) -> Result<Vec<<T::BellmanEngine as ScalarEngine>::Fr>, NoWitness> {
+        self.witness
+            .as_ref()
+            .map(|witness| self.public_inputs_values_from(witness))
+            .ok_or(NoWitness)
+    }
+
+    /// Extracts the public inputs from a witness which is not owned by this computation, panicking where
+    /// `try_public_inputs_values_from` fails
+    pub fn public_inputs_values_from(
+        &self,
+        witness: &Witness<T>,
+    ) -> Vec<<T::BellmanEngine as ScalarEngine>::Fr> {
+        self.try_public_inputs_values_from(witness)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Extracts the public inputs from a witness which is not owned by this computation, converting the values
+    /// of `Witness::public_view` to the scalar field of the curve
+    pub fn try_public_inputs_values_from(
+        &self,
+        witness: &Witness<T>,
+    ) -> Result<Vec<<T::BellmanEngine as ScalarEngine>::Fr>, PublicInputsError> {
+        witness
+            .public_view(&self.program)
+            .map(|values| values.into_iter().map(|v| v.into_bellman()).collect())
     }
 
     pub fn setup(self) -> Parameters<T::BellmanEngine> {
-        let rng = &mut ChaChaRng::new_unseeded();
-        // run setup phase
-        generate_random_parameters(self, rng).unwrap()
+        self.try_setup().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Runs the setup phase like `setup`, failing if the program has more constraints than the limit
+    pub fn try_setup(self) -> Result<Parameters<T::BellmanEngine>, CircuitTooLarge> {
+        self.setup_with(&mut ThreadRandomness)
+    }
+
+    /// Runs the setup phase like `try_setup`, drawing the toxic waste from `randomness`
+    pub fn setup_with<R: Randomness>(
+        self,
+        randomness: &mut R,
+    ) -> Result<Parameters<T::BellmanEngine>, CircuitTooLarge> {
+        self.check_size()?;
+
+        Ok(generate_random_parameters(self, &mut randomness.rng()).unwrap())
+    }
+
+    /// Runs the setup phase and only keeps the verifying key, dropping the proving key as soon as it is
+    /// generated. The toxic waste is drawn from `randomness`, so the key matches the one `setup_with` returns for
+    /// the same deterministic randomness.
+    pub fn verifying_key_only<R: Randomness>(
+        self,
+        randomness: &mut R,
+    ) -> Result<VerifyingKey<T::BellmanEngine>, SynthesisError> {
+        generate_random_parameters(self, &mut randomness.rng()).map(|params| params.vk)
+    }
+
+    /// Runs the setup phase with randomness derived from `entropy`, so that the same entropy always
+    /// yields the same parameters. This makes the setup reproducible by anyone holding the entropy,
+    /// which also means the toxic waste can be recomputed: this is not a secure ceremony.
+    pub fn setup_from_entropy(
+        self,
+        entropy: &[u8],
+    ) -> Result<Parameters<T::BellmanEngine>, SynthesisError> {
+        use sha2::{Digest, Sha256};
+
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&Sha256::digest(entropy));
+        let rng = &mut rng_from_seed_bytes(&seed);
+
+        generate_random_parameters(self, rng)
     }
 }
 
@@ -203,7 +727,10 @@ impl<T: Field> Circuit<T::BellmanEngine> for Computation<T> {
         self,
         cs: &mut CS,
     ) -> Result<(), SynthesisError> {
-        self.program.synthesize(cs, self.witness)
+        // bail out before allocating anything for a program over the limit
+        self.check_size()
+            .map_err(|_| SynthesisError::PolynomialDegreeTooLarge)?;
+        self.program.synthesize(cs, self.witness, None)
     }
 }
 
@@ -228,6 +755,9 @@ mod parse {
         static ref FR_REGEX: Regex = Regex::new(r"Fr\((?P<x>0[xX][0-9a-fA-F]*)\)").unwrap();
     }
 
+    // field elements are displayed with all their limbs, so the values below are zero-padded to the full width of
+    // the field (64 hex digits on bn128) no matter how small they are. Fixed-width consumers rely on this.
+
     pub fn parse_g1<T: Field>(
         e: &<T::BellmanEngine as bellman::pairing::Engine>::G1Affine,
     ) -> G1Affine {
@@ -239,6 +769,9 @@ mod parse {
         )
     }
 
+    /// Extracts the coordinates of a G2 point from its `Display` output. Each coordinate `c0 + c1 * u` of the
+    /// quadratic extension is returned as `(c1, c0)`, the imaginary part first, which is the order expected by the
+    /// pairing precompile of the EVM. The point at infinity has no coordinates and is not supported.
     pub fn parse_g2<T: Field>(
         e: &<T::BellmanEngine as bellman::pairing::Engine>::G2Affine,
     ) -> G2Affine {
@@ -266,12 +799,602 @@ mod parse {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{Function, LinComb};
+    use crate::ir::{Function, LinComb, ProgBuilder, QuadComb};
+    use crate::testing::identity;
     use ir::Interpreter;
     use zokrates_field::Bn128Field;
 
+    #[test]
+    fn verifier_public_inputs() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let interpreter = Interpreter::default();
+
+        let witness = interpreter
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+
+        let computation = Computation::with_witness(program, witness.clone());
+        let expected = computation.public_inputs_values();
+
+        let verifier = computation.into_verifier();
+        assert!(verifier.witness.is_none());
+        assert_eq!(verifier.public_inputs_values_from(&witness), expected);
+    }
+
+    #[test]
+    fn estimated_proving_key_bytes() {
+        let identity: Prog<Bn128Field> = identity(true);
+
+        let mut builder = ProgBuilder::<Bn128Field>::new();
+        let a = builder.public_input();
+        let b = builder.private_input();
+        let product = builder.variable();
+        builder.constrain(
+            QuadComb::from_linear_combinations(a.into(), LinComb::from(b) + LinComb::one()),
+            product,
+        );
+        builder.output(product);
+        builder.assert_boolean(b);
+        let quadratic = builder.build().unwrap();
+
+        for program in vec![identity, quadratic] {
+            let estimate = program.estimated_proving_key_bytes();
+
+            let mut bytes = vec![];
+            Computation::without_witness(program)
+                .setup()
+                .write(&mut bytes)
+                .unwrap();
+
+            assert_eq!(estimate, bytes.len());
+        }
+    }
+
+    #[test]
+    fn injected_randomness() {
+        let program: Prog<Bn128Field> = identity(true);
+        let computation = Computation::new(program, &[Bn128Field::from(42)]).unwrap();
+
+        let vk_bytes = |params: &Parameters<_>| {
+            let mut bytes = vec![];
+            params.vk.write(&mut bytes).unwrap();
+            bytes
+        };
+        let proof_bytes = |proof: Proof<_>| {
+            let mut bytes = vec![];
+            proof.write(&mut bytes).unwrap();
+            bytes
+        };
+
+        let params = computation
+            .clone()
+            .setup_with(&mut SeededRandomness([1; 8]))
+            .unwrap();
+        let again = computation
+            .clone()
+            .setup_with(&mut SeededRandomness([1; 8]))
+            .unwrap();
+        assert_eq!(vk_bytes(&params), vk_bytes(&again));
+
+        let other = computation
+            .clone()
+            .setup_with(&mut SeededRandomness([2; 8]))
+            .unwrap();
+        assert_ne!(vk_bytes(&params), vk_bytes(&other));
+
+        let proof = computation
+            .clone()
+            .prove_with(&params, &mut SeededRandomness([3; 8]));
+        let again = computation
+            .clone()
+            .prove_with(&params, &mut SeededRandomness([3; 8]));
+        assert_eq!(proof_bytes(proof), proof_bytes(again));
+
+        let secure = computation
+            .clone()
+            .setup_with(&mut ThreadRandomness)
+            .unwrap();
+        let _proof = computation.prove_with(&secure, &mut ThreadRandomness);
+    }
+
+    #[test]
+    fn one_as_argument() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::one(), FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    FlatVariable::new(0).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            private: vec![false, false],
+        };
+
+        let witness = Witness(
+            vec![
+                (FlatVariable::one(), Bn128Field::from(1)),
+                (FlatVariable::new(0), Bn128Field::from(2)),
+                (FlatVariable::public(0), Bn128Field::from(2)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            Computation::without_witness(program).try_public_inputs_values_from(&witness),
+            Err(PublicInputsError::OneAsArgument)
+        );
+        assert_eq!(
+            PublicInputsError::OneAsArgument.to_string(),
+            "~one is an implicit input and cannot be an argument of the program"
+        );
+    }
+
+    #[test]
+    fn circuit_too_large() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![
+                    Statement::Constraint(
+                        QuadComb::from_linear_combinations(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::new(0).into(),
+                        ),
+                        FlatVariable::new(1).into(),
+                    ),
+                    Statement::Constraint(
+                        FlatVariable::new(1).into(),
+                        FlatVariable::public(0).into(),
+                    ),
+                ],
+            },
+            private: vec![true],
+        };
+
+        let computation = Computation::new(program, &[Bn128Field::from(3)])
+            .unwrap()
+            .with_max_constraints(1);
+
+        assert_eq!(
+            computation.check_size(),
+            Err(CircuitTooLarge {
+                limit: 1,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            computation.clone().into_verifier().try_setup().err(),
+            Some(CircuitTooLarge {
+                limit: 1,
+                actual: 2
+            })
+        );
+        assert!(computation
+            .clone()
+            .verifying_key_only(&mut ThreadRandomness)
+            .is_err());
+
+        let params = computation
+            .clone()
+            .with_max_constraints(DEFAULT_MAX_CONSTRAINTS)
+            .setup();
+        assert!(create_random_proof(computation, &params, &mut ChaChaRng::new_unseeded()).is_err());
+    }
+
+    #[test]
+    fn circuit_mismatch() {
+        let program = |value: u32| -> Prog<Bn128Field> {
+            Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: vec![FlatVariable::new(0)],
+                    returns: vec![FlatVariable::public(0)],
+                    statements: vec![Statement::Constraint(
+                        (LinComb::from(FlatVariable::new(0))
+                            + LinComb::summand(value, FlatVariable::one()))
+                        .into(),
+                        FlatVariable::public(0).into(),
+                    )],
+                },
+                private: vec![true],
+            }
+        };
+
+        // parameters generated before the program was changed
+        let params = Computation::without_witness(program(1)).setup_with_circuit_hash();
+
+        let computation = Computation::new(program(2), &[Bn128Field::from(42)]).unwrap();
+        assert_eq!(
+            computation.prove_checked(&params).err(),
+            Some(CircuitMismatch {
+                expected: hex::encode(program(1).structural_hash()),
+                found: hex::encode(program(2).structural_hash()),
+            })
+        );
+
+        let computation = Computation::new(program(1), &[Bn128Field::from(42)]).unwrap();
+        assert!(computation.prove_checked(&params).is_ok());
+    }
+
+    #[test]
+    fn missing_return() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0), FlatVariable::public(1)],
+                statements: vec![
+                    Statement::Constraint(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::public(0).into(),
+                    ),
+                    Statement::Constraint(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::public(1).into(),
+                    ),
+                ],
+            },
+            private: vec![false],
+        };
+
+        let mut witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+        witness.0.remove(&FlatVariable::public(1));
+
+        let computation = Computation::without_witness(program);
+        assert_eq!(
+            computation.try_public_inputs_values_from(&witness),
+            Err(PublicInputsError::LengthMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            PublicInputsError::LengthMismatch {
+                expected: 3,
+                found: 2
+            }
+            .to_string(),
+            "Program has 3 public inputs but the witness holds 2"
+        );
+    }
+
+    #[test]
+    fn inputs_without_witness() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        assert_eq!(
+            Computation::without_witness(program.clone()).inputs(),
+            Err(NoWitness)
+        );
+        assert_eq!(
+            Computation::new(program, &[Bn128Field::from(42)])
+                .unwrap()
+                .inputs(),
+            Ok(vec![
+                Bn128Field::from(42).into_bellman(),
+                Bn128Field::from(42).into_bellman()
+            ])
+        );
+    }
+
+    #[test]
+    fn prove_without_clone() {
+        use bellman::pairing::bn256::Bn256;
+
+        let computation = Computation::new(identity(true), &[Bn128Field::from(42)]).unwrap();
+        let params = computation.clone().setup();
+
+        let expected = create_random_proof(
+            computation.clone(),
+            &params,
+            &mut ChaChaRng::from_seed(&[1; 8]),
+        )
+        .unwrap();
+        let proof = computation.prove_with(&params, &mut SeededRandomness([1; 8]));
+
+        let bytes = |p: &Proof<Bn256>| {
+            let mut bytes = vec![];
+            p.write(&mut bytes).unwrap();
+            bytes
+        };
+        assert_eq!(bytes(&proof), bytes(&expected));
+    }
+
+    #[test]
+    fn verifying_key_only() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let computation = Computation::new(program.clone(), &[Bn128Field::from(42)]).unwrap();
+        let params = computation
+            .clone()
+            .setup_with(&mut SeededRandomness([1; 8]))
+            .unwrap();
+        let inputs = computation.public_inputs_values();
+        let proof = computation.prove(&params);
+
+        let vk = Computation::without_witness(program)
+            .verifying_key_only(&mut SeededRandomness([1; 8]))
+            .unwrap();
+
+        assert!(verify_proof(&prepare_verifying_key(&vk), &proof, &inputs).unwrap());
+    }
+
+    #[test]
+    fn setup_from_entropy() {
+        let program: Prog<Bn128Field> = identity(true);
+
+        let vk = |entropy: &[u8]| {
+            let params = Computation::without_witness(program.clone())
+                .setup_from_entropy(entropy)
+                .unwrap();
+            let mut bytes = vec![];
+            params.vk.write(&mut bytes).unwrap();
+            bytes
+        };
+
+        assert_eq!(vk(b"ceremony"), vk(b"ceremony"));
+        assert_ne!(vk(b"ceremony"), vk(b"another ceremony"));
+    }
+
+    /// A constraint system recording the labels of allocated wires
+    #[derive(Default)]
+    struct Labels(Vec<String>);
+
+    impl<E: bellman::pairing::Engine> ConstraintSystem<E> for Labels {
+        type Root = Self;
+
+        fn alloc<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+        where
+            F: FnOnce() -> Result<E::Fr, SynthesisError>,
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            f()?;
+            self.0.push(annotation().into());
+            Ok(Variable::new_unchecked(bellman::Index::Aux(self.0.len())))
+        }
+
+        fn alloc_input<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+        where
+            F: FnOnce() -> Result<E::Fr, SynthesisError>,
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            f()?;
+            self.0.push(annotation().into());
+            Ok(Variable::new_unchecked(bellman::Index::Input(self.0.len())))
+        }
+
+        fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, _: LA, _: LB, _: LC)
+        where
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+            LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+            LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+            LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        {
+        }
+
+        fn push_namespace<NR, N>(&mut self, _: N)
+        where
+            NR: Into<String>,
+            N: FnOnce() -> NR,
+        {
+        }
+
+        fn pop_namespace(&mut self) {}
+
+        fn get_root(&mut self) -> &mut Self::Root {
+            self
+        }
+    }
+
+    #[test]
+    fn synthesize_with_names() {
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0), FlatVariable::new(1)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    (LinComb::from(FlatVariable::new(0)) + LinComb::from(FlatVariable::new(1)))
+                        .into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            private: vec![true, false],
+        };
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(1), Bn128Field::from(2)])
+            .unwrap();
+
+        let names = vec![
+            (FlatVariable::new(0), String::from("a")),
+            (FlatVariable::public(0), String::from("sum")),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut cs = Labels::default();
+        program
+            .clone()
+            .synthesize(&mut cs, Some(witness.clone()), Some(&names))
+            .unwrap();
+        assert_eq!(cs.0, vec!["a", "PUBLIC_INPUT_1", "sum"]);
+
+        let mut cs = Labels::default();
+        program.synthesize(&mut cs, Some(witness), None).unwrap();
+        assert_eq!(cs.0, vec!["PRIVATE_INPUT_0", "PUBLIC_INPUT_1", "~out_0"]);
+    }
+
+    #[test]
+    fn synthesize_counted() {
+        let square = |a: usize, b: usize| {
+            Statement::Constraint(
+                QuadComb::from_linear_combinations(
+                    FlatVariable::new(a).into(),
+                    FlatVariable::new(a).into(),
+                ),
+                FlatVariable::new(b).into(),
+            )
+        };
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![],
+                statements: vec![square(0, 1), square(1, 2), square(2, 3)],
+            },
+            private: vec![true],
+        };
+
+        let witness = Witness(
+            vec![
+                (FlatVariable::new(0), Bn128Field::from(2)),
+                (FlatVariable::new(1), Bn128Field::from(4)),
+                (FlatVariable::new(2), Bn128Field::from(16)),
+                (FlatVariable::new(3), Bn128Field::from(256)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            program
+                .clone()
+                .synthesize_counted(&mut Labels::default(), Some(witness.clone()), None)
+                .unwrap(),
+            3
+        );
+
+        // the value of `_2` is missing when synthesizing the second constraint
+        let mut partial = witness;
+        partial.0.remove(&FlatVariable::new(2));
+        match program.synthesize_counted(&mut Labels::default(), Some(partial), None) {
+            Err((1, SynthesisError::AssignmentMissing)) => {}
+            res => panic!("Expected a failure at constraint 1, found {:?}", res),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "is not reduced"))]
+    fn unreduced_coefficient() {
+        use bellman::pairing::bn256::Bn256;
+
+        // a coefficient equal to the field prime plus one, which `Field` arithmetic would never produce
+        let unreduced = Bn128Field::from_byte_vector(
+            (Bn128Field::max_value().to_biguint() + 2u32).to_bytes_le(),
+        );
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![Statement::Constraint(
+                    LinComb(vec![(FlatVariable::new(0), unreduced)]).into(),
+                    FlatVariable::public(0).into(),
+                )],
+            },
+            private: vec![false],
+        };
+
+        assert!(generate_random_parameters::<Bn256, _, _>(
+            Computation::without_witness(program),
+            &mut ChaChaRng::new_unseeded()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn symbol_backends() {
+        use bellman::pairing::bn256::Bn256;
+
+        /// A circuit synthesized with a given symbol table
+        struct WithSymbols<S>(Computation<Bn128Field>, std::marker::PhantomData<S>);
+
+        impl<S: Symbols> Circuit<Bn256> for WithSymbols<S> {
+            fn synthesize<CS: ConstraintSystem<Bn256>>(
+                self,
+                cs: &mut CS,
+            ) -> Result<(), SynthesisError> {
+                self.0
+                    .program
+                    .synthesize_with::<CS, S>(cs, self.0.witness, None)
+            }
+        }
+
+        fn proof<S: Symbols>(computation: Computation<Bn128Field>) -> (Vec<u8>, Vec<u8>) {
+            let params = generate_random_parameters(
+                WithSymbols::<S>(
+                    computation.clone().into_verifier(),
+                    std::marker::PhantomData,
+                ),
+                &mut ChaChaRng::new_unseeded(),
+            )
+            .unwrap();
+            let proof = create_random_proof(
+                WithSymbols::<S>(computation, std::marker::PhantomData),
+                &params,
+                &mut ChaChaRng::new_unseeded(),
+            )
+            .unwrap();
+
+            let mut params_bytes = vec![];
+            params.write(&mut params_bytes).unwrap();
+            let mut proof_bytes = vec![];
+            proof.write(&mut proof_bytes).unwrap();
+            (params_bytes, proof_bytes)
+        }
+
+        let program: Prog<Bn128Field> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0), FlatVariable::new(1)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![
+                    Statement::Constraint(
+                        QuadComb::from_linear_combinations(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::new(1).into(),
+                        ),
+                        FlatVariable::new(2).into(),
+                    ),
+                    Statement::Constraint(
+                        (LinComb::from(FlatVariable::new(2)) + LinComb::from(FlatVariable::new(0)))
+                            .into(),
+                        FlatVariable::public(0).into(),
+                    ),
+                ],
+            },
+            private: vec![true, false],
+        };
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(3), Bn128Field::from(4)])
+            .unwrap();
+
+        let computation = Computation::with_witness(program, witness);
+
+        assert_eq!(
+            proof::<BTreeMap<FlatVariable, Variable>>(computation.clone()),
+            proof::<HashMap<FlatVariable, Variable>>(computation)
+        );
+    }
+
     mod prove {
         use super::*;
+        use crate::testing::{assert_prove_verify, increment};
 
         #[test]
         fn empty() {
@@ -285,13 +1408,35 @@ mod tests {
                 private: vec![],
             };
 
+            assert_prove_verify(program, &[]);
+        }
+
+        #[test]
+        fn empty_verify() {
+            let program: Prog<Bn128Field> = Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: vec![],
+                    returns: vec![],
+                    statements: vec![],
+                },
+                private: vec![],
+            };
+
+            assert!(Computation::without_witness(program.clone())
+                .public_inputs_values()
+                .is_empty());
+
             let interpreter = Interpreter::default();
 
             let witness = interpreter.execute(&program, &vec![]).unwrap();
             let computation = Computation::with_witness(program, witness);
 
             let params = computation.clone().setup();
-            let _proof = computation.prove(&params);
+            let proof = computation.prove(&params);
+
+            let pvk = prepare_verifying_key(&params.vk);
+            assert!(verify_proof(&pvk, &proof, &[]).unwrap());
         }
 
         #[test]
@@ -336,6 +1481,9 @@ mod tests {
                 private: vec![false],
             };
 
+            assert!(!program.has_private_inputs());
+            assert!(program.private_variables().is_empty());
+
             let interpreter = Interpreter::default();
 
             let witness = interpreter
@@ -363,20 +1511,12 @@ mod tests {
                 private: vec![],
             };
 
-            let interpreter = Interpreter::default();
-
-            let witness = interpreter.execute(&program, &vec![]).unwrap();
-            let computation = Computation::with_witness(program, witness);
-
-            let params = computation.clone().setup();
-            let _proof = computation.prove(&params);
+            assert_prove_verify(program, &[]);
         }
 
-        #[test]
-        fn unordered_variables() {
-            // public variables must be ordered from 0
-            // private variables can be unordered
-            let program: Prog<Bn128Field> = Prog {
+        // the program of `unordered_variables`, with the private argument `_42` and the public argument `_51`
+        fn unordered_program() -> Prog<Bn128Field> {
+            Prog {
                 main: Function {
                     id: String::from("main"),
                     arguments: vec![FlatVariable::new(42), FlatVariable::new(51)],
@@ -397,19 +1537,176 @@ mod tests {
                     ],
                 },
                 private: vec![true, false],
-            };
+            }
+        }
+
+        #[test]
+        fn unordered_variables() {
+            // public variables must be ordered from 0
+            // private variables can be unordered
+            let program = unordered_program();
 
             let interpreter = Interpreter::default();
 
             let witness = interpreter
                 .execute(&program, &vec![Bn128Field::from(3), Bn128Field::from(4)])
                 .unwrap();
+
             let computation = Computation::with_witness(program, witness);
 
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
         }
 
+        #[test]
+        fn unordered_variables_labels() {
+            let program = unordered_program();
+
+            let witness = Interpreter::default()
+                .execute(&program, &vec![Bn128Field::from(3), Bn128Field::from(4)])
+                .unwrap();
+
+            // each return value is tagged with its index, and source names are attached when known
+            let names = vec![
+                (FlatVariable::new(51), String::from("b")),
+                (FlatVariable::public(1), String::from("b_plus_one")),
+            ]
+            .into_iter()
+            .collect();
+            let labels = program.public_input_labels(Some(&names));
+            assert_eq!(
+                labels,
+                vec![
+                    ir::PublicInputLabel {
+                        variable: String::from("_51"),
+                        output: None,
+                        name: Some(String::from("b")),
+                    },
+                    ir::PublicInputLabel {
+                        variable: String::from("~out_0"),
+                        output: Some(0),
+                        name: None,
+                    },
+                    ir::PublicInputLabel {
+                        variable: String::from("~out_1"),
+                        output: Some(1),
+                        name: Some(String::from("b_plus_one")),
+                    },
+                ]
+            );
+
+            let grouped: serde_json::Value =
+                serde_json::from_str(&witness.to_grouped_json(&program, Some(&names)).unwrap())
+                    .unwrap();
+            assert_eq!(
+                grouped,
+                serde_json::json!({
+                    "arguments": [{"variable": "_51", "name": "b", "value": "4"}],
+                    "outputs": [
+                        {"variable": "~out_0", "output": 0, "value": "7"},
+                        {"variable": "~out_1", "output": 1, "name": "b_plus_one", "value": "4"},
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn unordered_variables_truncated() {
+            let program = unordered_program();
+
+            // the first constraint only defines `~out_0`
+            let truncated = program.truncate(1);
+            assert_eq!(truncated.main.returns, vec![FlatVariable::public(0)]);
+            assert_prove_verify(truncated, &[Bn128Field::from(3), Bn128Field::from(4)]);
+        }
+
+        #[test]
+        fn unordered_variables_set() {
+            let program = unordered_program();
+
+            let witness = Interpreter::default()
+                .execute(&program, &vec![Bn128Field::from(3), Bn128Field::from(4)])
+                .unwrap();
+
+            // `_42`, `_51`, `~out_0` and `~out_1`, and `~one` which appears in the second constraint
+            assert_eq!(program.variables(false).len(), 4);
+            assert_eq!(program.variables(true).len(), 5);
+            assert_eq!(
+                program.variables(true).into_iter().collect::<Vec<_>>(),
+                witness.0.keys().cloned().collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn unordered_variables_public_view() {
+            let program = unordered_program();
+
+            let witness = Interpreter::default()
+                .execute(&program, &vec![Bn128Field::from(3), Bn128Field::from(4)])
+                .unwrap();
+
+            // the public argument `_51`, then the returns `~out_0` and `~out_1`
+            let public = witness.public_view(&program).unwrap();
+            assert_eq!(
+                public,
+                vec![
+                    Bn128Field::from(4),
+                    Bn128Field::from(7),
+                    Bn128Field::from(4)
+                ]
+            );
+            assert_eq!(public[0], witness.0[&FlatVariable::new(51)]);
+            assert_eq!(public[1..].to_vec(), witness.return_values());
+
+            let mut short = witness.clone();
+            short.0.remove(&FlatVariable::new(51));
+            assert_eq!(
+                short.public_view(&program),
+                Err(PublicInputsError::LengthMismatch {
+                    expected: 3,
+                    found: 2
+                })
+            );
+
+            let computation = Computation::with_witness(program, witness);
+            assert_eq!(
+                computation.public_inputs_values(),
+                public
+                    .into_iter()
+                    .map(|v| v.into_bellman())
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn from_inputs() {
+            let program: Prog<Bn128Field> = increment();
+
+            assert_eq!(
+                Computation::new(program.clone(), &[]).err(),
+                Some(ir::Error::WrongInputCount {
+                    expected: 1,
+                    received: 0
+                })
+            );
+
+            let computation = Computation::new(program, &[Bn128Field::from(3)]).unwrap();
+            let public_inputs = computation.public_inputs_values();
+            assert_eq!(
+                public_inputs,
+                vec![
+                    Bn128Field::from(3).into_bellman(),
+                    Bn128Field::from(4).into_bellman()
+                ]
+            );
+
+            let params = computation.clone().setup();
+            let proof = computation.prove(&params);
+
+            let pvk = prepare_verifying_key(&params.vk);
+            assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+        }
+
         #[test]
         fn one() {
             let program: Prog<Bn128Field> = Prog {
@@ -431,6 +1728,17 @@ mod tests {
                 .execute(&program, &vec![Bn128Field::from(3)])
                 .unwrap();
 
+            let names = vec![(FlatVariable::new(42), String::from("a"))]
+                .into_iter()
+                .collect();
+            let json: serde_json::Value =
+                serde_json::from_str(&witness.to_json(Some(&names))).unwrap();
+            assert_eq!(json["~out_0"], "4");
+            assert_eq!(json["a"], "3");
+            assert_eq!(json["~one"], "1");
+            assert_eq!(json.as_object().unwrap().len(), 3);
+            assert!(witness.to_string().lines().any(|l| l == "~out_0 = 4"));
+
             let computation = Computation::with_witness(program, witness);
 
             let params = computation.clone().setup();
@@ -454,6 +1762,16 @@ mod tests {
                 private: vec![true, false],
             };
 
+            assert_eq!(
+                program.input_signature(),
+                vec![
+                    (FlatVariable::new(42), true),
+                    (FlatVariable::new(51), false)
+                ]
+            );
+            assert!(program.has_private_inputs());
+            assert_eq!(program.private_variables(), vec![FlatVariable::new(42)]);
+
             let interpreter = Interpreter::default();
 
             let witness = interpreter
@@ -464,5 +1782,46 @@ mod tests {
             let params = computation.clone().setup();
             let _proof = computation.prove(&params);
         }
+
+        #[test]
+        fn would_verify() {
+            let mut builder = ProgBuilder::<Bn128Field>::new();
+            let a = builder.private_input();
+            let b = builder.variable();
+            builder.constrain(QuadComb::from_linear_combinations(a.into(), a.into()), b);
+            builder.output(b);
+            let program = builder.build().unwrap();
+
+            let mut witness = Interpreter::default()
+                .execute(&program, &vec![Bn128Field::from(3)])
+                .unwrap();
+
+            let computation = Computation::with_witness(program.clone(), witness.clone());
+            assert!(computation.would_verify().unwrap());
+            assert!(computation.clone().into_verifier().would_verify().is_err());
+
+            witness.0.insert(b, Bn128Field::from(10));
+            let tampered = Computation::with_witness(program, witness);
+            assert!(!tampered.would_verify().unwrap());
+        }
+    }
+
+    mod parse {
+        use super::*;
+        use bellman::pairing::bn256::{Fr, G1Affine};
+        use bellman::pairing::ff::PrimeField;
+        use bellman::pairing::CurveAffine;
+
+        #[test]
+        fn padded_coordinates() {
+            // the generator of G1 is (1, 2)
+            let g1 = parse_g1::<Bn128Field>(&G1Affine::one());
+            assert_eq!(g1.0, format!("0x{:0>64}", "1"));
+            assert_eq!(g1.1, format!("0x{:0>64}", "2"));
+
+            let fr = parse_fr::<Bn128Field>(&Fr::from_str("255").unwrap());
+            assert_eq!(fr.len(), 66);
+            assert!(fr.ends_with("00ff"));
+        }
     }
 }
@@ -0,0 +1,157 @@
+//! Byte-in/byte-out proving and verification for environments without a filesystem or
+//! `thread_rng`, such as the browser. Every artifact is passed in its binary encoding:
+//!
+//! * programs as written by `Prog::serialize`
+//! * witnesses as written by `Witness::write`
+//! * parameters, verifying keys and proofs as written by bellman
+//! * public inputs as a concatenation of 32-byte big-endian field elements
+
+use bellman::groth16::{
+    create_random_proof, prepare_verifying_key, verify_proof, Parameters, Proof, VerifyingKey,
+};
+use bellman::pairing::bn256::{Bn256, Fr};
+use bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
+use zokrates_field::Bn128Field;
+
+use super::rand::ChaChaRng;
+use super::{rng_from_seed_bytes, Computation};
+use crate::ir::{ProgEnum, Witness};
+
+const FR_BYTES: usize = 32;
+
+pub(super) fn rng() -> Result<ChaChaRng, String> {
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed)
+        .map_err(|why| format!("Could not gather randomness: {}", why))?;
+
+    Ok(rng_from_seed_bytes(&seed))
+}
+
+fn decode_inputs(inputs_bytes: &[u8]) -> Result<Vec<Fr>, String> {
+    if inputs_bytes.len() % FR_BYTES != 0 {
+        return Err(format!(
+            "Invalid inputs length: expected a multiple of {} bytes, got {}",
+            FR_BYTES,
+            inputs_bytes.len()
+        ));
+    }
+
+    inputs_bytes
+        .chunks(FR_BYTES)
+        .map(|chunk| {
+            let mut repr = <Fr as PrimeField>::Repr::default();
+            repr.read_be(chunk)
+                .map_err(|why| format!("Could not read input: {}", why))?;
+            Fr::from_repr(repr).map_err(|why| format!("Invalid input: {}", why))
+        })
+        .collect()
+}
+
+/// Encodes public inputs in the format expected by `verify_wasm`
+pub fn encode_inputs(inputs: &[Fr]) -> Vec<u8> {
+    let mut res = Vec::with_capacity(inputs.len() * FR_BYTES);
+    for input in inputs {
+        input.into_repr().write_be(&mut res).unwrap();
+    }
+    res
+}
+
+/// Generates a Groth16 proof and returns it in bellman's binary encoding
+pub fn prove_wasm(
+    prog_bytes: &[u8],
+    witness_bytes: &[u8],
+    params_bytes: &[u8],
+) -> Result<Vec<u8>, String> {
+    let program = match ProgEnum::deserialize(prog_bytes)? {
+        ProgEnum::Bn128Program(p) => p,
+        _ => return Err(String::from("Only bn128 programs are supported")),
+    };
+
+    let witness = Witness::<Bn128Field>::read(witness_bytes)
+        .map_err(|why| format!("Could not read witness: {}", why))?;
+
+    let params = Parameters::<Bn256>::read(params_bytes, true)
+        .map_err(|why| format!("Could not read parameters: {}", why))?;
+
+    let computation = Computation::with_witness(program, witness);
+
+    let proof = create_random_proof(computation, &params, &mut rng()?)
+        // the `Display` implementation of `SynthesisError` does not terminate, so `Debug` is used instead
+        .map_err(|why| format!("Could not generate proof: {:?}", why))?;
+
+    let mut res = vec![];
+    proof
+        .write(&mut res)
+        .map_err(|why| format!("Could not write proof: {}", why))?;
+
+    Ok(res)
+}
+
+/// Verifies a Groth16 proof against a verifying key and public inputs
+pub fn verify_wasm(
+    vk_bytes: &[u8],
+    proof_bytes: &[u8],
+    inputs_bytes: &[u8],
+) -> Result<bool, String> {
+    let vk = VerifyingKey::<Bn256>::read(vk_bytes)
+        .map_err(|why| format!("Could not read verifying key: {}", why))?;
+
+    let proof = Proof::<Bn256>::read(proof_bytes)
+        .map_err(|why| format!("Could not read proof: {}", why))?;
+
+    let inputs = decode_inputs(inputs_bytes)?;
+
+    let pvk = prepare_verifying_key(&vk);
+
+    verify_proof(&pvk, &proof, &inputs).map_err(|why| format!("Could not verify proof: {:?}", why))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Interpreter, Prog};
+    use crate::testing::identity;
+    use zokrates_field::Field;
+
+    #[test]
+    fn prove_and_verify_bytes() {
+        let program: Prog<Bn128Field> = identity(false);
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(42)])
+            .unwrap();
+
+        let params = Computation::without_witness(program.clone()).setup();
+
+        let mut prog_bytes = vec![];
+        program.serialize(&mut prog_bytes);
+
+        let mut witness_bytes = vec![];
+        witness.write(&mut witness_bytes).unwrap();
+
+        let mut params_bytes = vec![];
+        params.write(&mut params_bytes).unwrap();
+
+        let mut vk_bytes = vec![];
+        params.vk.write(&mut vk_bytes).unwrap();
+
+        let proof_bytes = prove_wasm(&prog_bytes, &witness_bytes, &params_bytes).unwrap();
+
+        let inputs = Computation::with_witness(program, witness).public_inputs_values();
+
+        assert!(verify_wasm(&vk_bytes, &proof_bytes, &encode_inputs(&inputs)).unwrap());
+
+        let wrong_inputs = encode_inputs(&[
+            Bn128Field::from(41).into_bellman(),
+            Bn128Field::from(41).into_bellman(),
+        ]);
+        assert!(!verify_wasm(&vk_bytes, &proof_bytes, &wrong_inputs).unwrap());
+
+        assert_eq!(
+            verify_wasm(&vk_bytes, &proof_bytes, &encode_inputs(&inputs[..1])),
+            Err(String::from(
+                "Could not verify proof: MalformedVerifyingKey"
+            ))
+        );
+    }
+}
@@ -2,11 +2,15 @@ pub mod bellman;
 #[cfg(feature = "libsnark")]
 pub mod libsnark;
 
+mod base64;
+mod keccak;
 mod solidity;
 
+use crate::error::ZokratesError;
 use crate::ir;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::fmt;
 use zokrates_field::Field;
 
 // We only need to serialize this struct, there is no need for deserialization as keys are
@@ -38,16 +42,189 @@ impl SolidityAbi {
     }
 }
 
+/// Information on how and when a proof was generated, stored along with it for auditability
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ProofMeta {
+    /// The time at which the proof was generated, in seconds since the Unix epoch
+    pub created_at: u64,
+    pub scheme: String,
+    pub curve: String,
+}
+
+impl ProofMeta {
+    /// Returns the metadata of a proof generated now with `scheme` on the curve of `T`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn now<T: Field>(scheme: &str) -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        ProofMeta {
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            scheme: String::from(scheme),
+            curve: String::from(T::name()),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Proof<T> {
     proof: T,
     inputs: Vec<String>,
     raw: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vk_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    meta: Option<ProofMeta>,
 }
 
 impl<T: Serialize + DeserializeOwned> Proof<T> {
     fn new(proof: T, inputs: Vec<String>, raw: String) -> Self {
-        Proof { proof, inputs, raw }
+        Proof {
+            proof,
+            inputs,
+            raw,
+            vk_id: None,
+            meta: None,
+        }
+    }
+
+    fn with_vk_id(self, vk_id: String) -> Self {
+        Proof {
+            vk_id: Some(vk_id),
+            ..self
+        }
+    }
+}
+
+/// Serializes `proof` to JSON, attaching `meta` in a `meta` block if provided. The other fields are left unchanged,
+/// so that parsers unaware of the metadata can still read the proof.
+pub fn serialize_proof<T: Serialize>(proof: Proof<T>, meta: Option<ProofMeta>) -> String {
+    serde_json::to_string_pretty(&Proof { meta, ..proof }).unwrap()
+}
+
+/// Writes the message of the errors raised when `got` public inputs are provided to a verification key which
+/// expects `expected`
+pub(crate) fn write_wrong_input_count(
+    f: &mut fmt::Formatter,
+    expected: usize,
+    got: usize,
+) -> fmt::Result {
+    write!(
+        f,
+        "Verification key expects {} public input{} but {} {} provided",
+        expected,
+        if expected == 1 { "" } else { "s" },
+        got,
+        if got == 1 { "was" } else { "were" }
+    )
+}
+
+#[derive(Debug, PartialEq)]
+pub enum VerificationError {
+    VkMismatch {
+        expected: String,
+        found: String,
+    },
+    /// The verification key has a point for `expected` public inputs, but `got` inputs were provided
+    WrongInputCount {
+        expected: usize,
+        got: usize,
+    },
+    /// The verification key does not have the point of the constant term, so it cannot be used at all
+    MalformedKey,
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerificationError::VkMismatch {
+                ref expected,
+                ref found,
+            } => write!(
+                f,
+                "Proof was generated for verification key {} but verification key {} was provided",
+                found, expected
+            ),
+            VerificationError::WrongInputCount { expected, got } => {
+                write_wrong_input_count(f, expected, got)
+            }
+            VerificationError::MalformedKey => {
+                write!(f, "Verification key has no point for the constant term")
+            }
+        }
+    }
+}
+
+/// The error returned when public inputs given as strings cannot be used to verify a proof
+#[derive(Debug, PartialEq)]
+pub enum InputError {
+    /// The input is neither a decimal nor a `0x`-prefixed hexadecimal number
+    Invalid(String),
+    /// The input is not smaller than the field modulus
+    OutOfRange(String),
+    WrongCount {
+        expected: usize,
+        got: usize,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InputError::Invalid(ref s) => write!(f, "Invalid public input: {}", s),
+            InputError::OutOfRange(ref s) => write!(
+                f,
+                "Public input {} is not smaller than the field modulus",
+                s
+            ),
+            InputError::WrongCount { expected, got } => write_wrong_input_count(f, expected, got),
+        }
+    }
+}
+
+/// The error returned when a point of a proof is not a valid group element. Each variant holds the name of the
+/// point, `A`, `B` or `C`.
+#[derive(Debug, PartialEq)]
+pub enum PointError {
+    NotOnCurve(&'static str),
+    /// The point is on the curve but not in the subgroup of prime order
+    NotInSubgroup(&'static str),
+}
+
+impl fmt::Display for PointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PointError::NotOnCurve(point) => {
+                write!(f, "Point {} of the proof is not on the curve", point)
+            }
+            PointError::NotInSubgroup(point) => write!(
+                f,
+                "Point {} of the proof is not in the subgroup of prime order",
+                point
+            ),
+        }
+    }
+}
+
+/// The outcome of a verification, detailing which check failed if any
+#[derive(Debug, PartialEq)]
+pub enum VerifyResult {
+    Ok,
+    WrongInputCount { expected: usize, got: usize },
+    PairingFailed,
+}
+
+impl fmt::Display for VerifyResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifyResult::Ok => write!(f, "Proof is valid"),
+            VerifyResult::WrongInputCount { expected, got } => {
+                write_wrong_input_count(f, expected, got)
+            }
+            VerifyResult::PairingFailed => write!(f, "Pairing check failed"),
+        }
     }
 }
 
@@ -85,7 +262,26 @@ where
         proving_key: Vec<u8>,
     ) -> Proof<Self::ProofPoints>;
 
+    /// Generates a proof like `generate_proof`, but fails instead of panicking if `proving_key` cannot be read
+    fn try_generate_proof(
+        program: ir::Prog<T>,
+        witness: ir::Witness<T>,
+        proving_key: Vec<u8>,
+    ) -> Result<Proof<Self::ProofPoints>, ZokratesError> {
+        Ok(Self::generate_proof(program, witness, proving_key))
+    }
+
     fn export_solidity_verifier(vk: Self::VerificationKey, abi: SolidityAbi) -> String;
 
     fn verify(vk: Self::VerificationKey, proof: Proof<Self::ProofPoints>) -> bool;
+
+    /// Serializes a verification key to the JSON format read by `verify`
+    fn serialize_vk(vk: &Self::VerificationKey) -> String {
+        serde_json::to_string_pretty(vk).unwrap()
+    }
+
+    /// Serializes a proof to the JSON format read by `verify`
+    fn serialize_proof(proof: &Proof<Self::ProofPoints>) -> String {
+        serde_json::to_string_pretty(proof).unwrap()
+    }
 }
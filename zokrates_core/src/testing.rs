@@ -0,0 +1,113 @@
+//! Helpers for testing circuits end to end, available in the tests of this crate and to other crates through the
+//! `testing` feature
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Function, Interpreter, LinComb, Prog, Statement, Witness};
+use crate::proof_system::bellman::Computation;
+use bellman::groth16::{prepare_verifying_key, verify_proof, Parameters, Proof};
+use bellman::pairing::bn256::{Bn256, Fr};
+use zokrates_field::{Bn128Field, Field};
+
+/// Returns the program which returns its only argument, that argument being private if `private` is set
+pub fn identity<T: Field>(private: bool) -> Prog<T> {
+    Prog {
+        main: Function {
+            id: String::from("main"),
+            arguments: vec![FlatVariable::new(0)],
+            returns: vec![FlatVariable::public(0)],
+            statements: vec![Statement::Constraint(
+                FlatVariable::new(0).into(),
+                FlatVariable::public(0).into(),
+            )],
+        },
+        private: vec![private],
+    }
+}
+
+/// Returns the program which returns its only argument `_42` plus one, that argument being public
+pub fn increment<T: Field>() -> Prog<T> {
+    Prog {
+        main: Function {
+            id: String::from("main"),
+            arguments: vec![FlatVariable::new(42)],
+            returns: vec![FlatVariable::public(0)],
+            statements: vec![Statement::Constraint(
+                (LinComb::from(FlatVariable::new(42)) + LinComb::one()).into(),
+                FlatVariable::public(0).into(),
+            )],
+        },
+        private: vec![false],
+    }
+}
+
+/// Sets up `identity(private)` and proves it on `42`, returning the parameters, the proof and its public inputs
+pub fn identity_proof(private: bool) -> (Parameters<Bn256>, Proof<Bn256>, Vec<Fr>) {
+    let computation = Computation::new(identity(private), &[Bn128Field::from(42)]).unwrap();
+    let inputs = computation.public_inputs_values();
+
+    let params = computation.clone().setup();
+    let proof = computation.prove(&params);
+
+    (params, proof, inputs)
+}
+
+/// Runs `program` on `inputs`, then sets it up, proves it and verifies the proof with its public inputs
+///
+/// # Panics
+///
+/// Panics with a message describing the failing step if the program cannot be executed on `inputs`, if the
+/// resulting witness does not satisfy the constraints, or if the proof does not verify.
+pub fn assert_prove_verify<T: Field>(program: Prog<T>, inputs: &[T]) {
+    let witness = Interpreter::default()
+        .execute(&program, &inputs.to_vec())
+        .unwrap_or_else(|e| panic!("Execution failed on inputs {:?}: {}", inputs, e));
+
+    assert_witness_verifies(program, witness);
+}
+
+/// Sets `program` up, proves it with `witness` and verifies the proof with its public inputs
+///
+/// # Panics
+///
+/// Panics with a message describing the failing step if `witness` does not satisfy the constraints, or if the
+/// proof does not verify.
+pub fn assert_witness_verifies<T: Field>(program: Prog<T>, witness: Witness<T>) {
+    if let Err(unsatisfied) = program.check_constraints(&witness) {
+        panic!(
+            "The witness does not satisfy the constraints at {:?}",
+            unsatisfied
+        );
+    }
+
+    let computation = Computation::with_witness(program, witness);
+    let public_inputs = computation.public_inputs_values();
+
+    let params = computation.clone().setup();
+    let proof = computation.prove(&params);
+
+    match verify_proof(&prepare_verifying_key(&params.vk), &proof, &public_inputs) {
+        Ok(true) => {}
+        Ok(false) => panic!(
+            "The proof does not verify with public inputs {:?}",
+            public_inputs
+        ),
+        Err(e) => panic!("Verification failed: {:?}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn valid() {
+        assert_prove_verify(identity(false), &[Bn128Field::from(42)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Execution failed on inputs")]
+    fn wrong_input_count() {
+        assert_prove_verify(identity::<Bn128Field>(false), &[]);
+    }
+}
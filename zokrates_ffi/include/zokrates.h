@@ -0,0 +1,42 @@
+/* C bindings for proving and verification with the G16 scheme, implemented in src/lib.rs */
+
+#ifndef ZOKRATES_H
+#define ZOKRATES_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ZOK_ERR_NULL_POINTER -1
+#define ZOK_ERR_PROGRAM -2
+#define ZOK_ERR_WITNESS -3
+#define ZOK_ERR_KEY -4
+#define ZOK_ERR_PROOF -5
+#define ZOK_ERR_INPUTS -6
+#define ZOK_ERR_BUFFER_TOO_SMALL -7
+#define ZOK_ERR_INTERNAL -8
+#define ZOK_ERR_CURVE -9
+
+#define ZOK_CURVE_BN128 0
+#define ZOK_CURVE_BLS12_381 1
+
+/* Writes a proof for the program and its .wtns witness to out, returning its length or a ZOK_ERR_* code */
+int zok_prove(const uint8_t *prog_ptr, size_t prog_len,
+              const uint8_t *witness_ptr, size_t witness_len,
+              const uint8_t *params_ptr, size_t params_len,
+              uint8_t *out_ptr, size_t out_cap);
+
+/* Verifies a proof on a ZOK_CURVE_* curve, returning 1 if it is valid, 0 if it is not, or a ZOK_ERR_* code */
+int zok_verify(int curve,
+               const uint8_t *vk_ptr, size_t vk_len,
+               const uint8_t *proof_ptr, size_t proof_len,
+               const uint8_t *inputs_ptr, size_t inputs_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ZOKRATES_H */
@@ -0,0 +1,267 @@
+//! C-callable proving and verification for the G16 scheme, declared in `include/zokrates.h`. The crate
+//! is built as a static and a shared library for linking from C.
+//!
+//! All buffers use the binary serializers of `zokrates_core`: `Prog::serialize` for programs, the `.wtns` format for
+//! witnesses, bellman's `Parameters::write` for proving keys, `write_pvk` for verification keys, bellman's
+//! `Proof::write` for proofs and `write_inputs` for public inputs. Failures are reported as negative return
+//! codes, and panics are caught so that they never unwind across the boundary.
+
+use bellman_ce::groth16::{verify_proof, Parameters, Proof};
+use std::os::raw::c_int;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+use zokrates_core::ir::{Prog, ProgEnum, Witness};
+use zokrates_core::proof_system::bellman::groth16::{read_inputs, read_pvk};
+use zokrates_core::proof_system::bellman::Computation;
+use zokrates_field::{Bls12Field, Bn128Field, Field};
+
+/// The bn128 curve, as the `curve` argument of `zok_verify`
+pub const ZOK_CURVE_BN128: c_int = 0;
+/// The bls12_381 curve, as the `curve` argument of `zok_verify`
+pub const ZOK_CURVE_BLS12_381: c_int = 1;
+
+/// A pointer is null while its length is not zero
+pub const ZOK_ERR_NULL_POINTER: c_int = -1;
+/// The program could not be deserialized
+pub const ZOK_ERR_PROGRAM: c_int = -2;
+/// The witness could not be read or does not satisfy the program
+pub const ZOK_ERR_WITNESS: c_int = -3;
+/// The proving or verification key could not be read
+pub const ZOK_ERR_KEY: c_int = -4;
+/// The proof could not be read
+pub const ZOK_ERR_PROOF: c_int = -5;
+/// The public inputs could not be read
+pub const ZOK_ERR_INPUTS: c_int = -6;
+/// The output buffer is too small for the result
+pub const ZOK_ERR_BUFFER_TOO_SMALL: c_int = -7;
+/// An unexpected error occurred, for example a proving key generated for another program
+pub const ZOK_ERR_INTERNAL: c_int = -8;
+/// The curve is not one of the `ZOK_CURVE_*` constants
+pub const ZOK_ERR_CURVE: c_int = -9;
+
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], c_int> {
+    match (ptr.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(ZOK_ERR_NULL_POINTER),
+        (false, _) => Ok(slice::from_raw_parts(ptr, len)),
+    }
+}
+
+fn guard<F: FnOnce() -> Result<c_int, c_int>>(f: F) -> c_int {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(res)) => res,
+        Ok(Err(code)) => code,
+        Err(_) => ZOK_ERR_INTERNAL,
+    }
+}
+
+fn prove<T: Field>(program: Prog<T>, witness: &[u8], params: &[u8]) -> Result<Vec<u8>, c_int> {
+    let witness = Witness::read_wtns_binary(&program, witness).map_err(|_| ZOK_ERR_WITNESS)?;
+    program
+        .check_constraints(&witness)
+        .map_err(|_| ZOK_ERR_WITNESS)?;
+    let params = Parameters::<T::BellmanEngine>::read(params, true).map_err(|_| ZOK_ERR_KEY)?;
+
+    let proof = Computation::with_witness(program, witness).prove(&params);
+
+    let mut res = vec![];
+    proof.write(&mut res).map_err(|_| ZOK_ERR_INTERNAL)?;
+    Ok(res)
+}
+
+/// Generates a proof for the program and witness given as `(pointer, length)` pairs, using the proving key in
+/// `params`. The proof is written to `out`, and its length in bytes is returned. A negative `ZOK_ERR_*` code is
+/// returned on failure, in which case `out` is left untouched.
+///
+/// # Safety
+///
+/// Each pointer must be valid for its length, and `out` must be valid for writes of `out_cap` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zok_prove(
+    prog_ptr: *const u8,
+    prog_len: usize,
+    witness_ptr: *const u8,
+    witness_len: usize,
+    params_ptr: *const u8,
+    params_len: usize,
+    out_ptr: *mut u8,
+    out_cap: usize,
+) -> c_int {
+    guard(|| {
+        let program = bytes(prog_ptr, prog_len)?;
+        let witness = bytes(witness_ptr, witness_len)?;
+        let params = bytes(params_ptr, params_len)?;
+
+        let proof = match ProgEnum::deserialize(program).map_err(|_| ZOK_ERR_PROGRAM)? {
+            ProgEnum::Bn128Program(p) => prove(p, witness, params),
+            ProgEnum::Bls12Program(p) => prove(p, witness, params),
+        }?;
+
+        if proof.len() > out_cap {
+            return Err(ZOK_ERR_BUFFER_TOO_SMALL);
+        }
+        if out_ptr.is_null() {
+            return Err(ZOK_ERR_NULL_POINTER);
+        }
+        slice::from_raw_parts_mut(out_ptr, proof.len()).copy_from_slice(&proof);
+        Ok(proof.len() as c_int)
+    })
+}
+
+fn verify<T: Field>(vk: &[u8], proof: &[u8], inputs: &[u8]) -> Result<c_int, c_int> {
+    let pvk = read_pvk::<T, _>(vk).map_err(|_| ZOK_ERR_KEY)?;
+    let proof = Proof::read(proof).map_err(|_| ZOK_ERR_PROOF)?;
+    let inputs = read_inputs::<T, _>(inputs).map_err(|_| ZOK_ERR_INPUTS)?;
+
+    match verify_proof(&pvk, &proof, &inputs) {
+        Ok(true) => Ok(1),
+        Ok(false) => Ok(0),
+        // a wrong number of public inputs
+        Err(_) => Err(ZOK_ERR_INPUTS),
+    }
+}
+
+/// Verifies a proof on `curve`, one of the `ZOK_CURVE_*` constants, against a verification key and public inputs
+/// given as `(pointer, length)` pairs. Returns 1 if the proof is valid, 0 if it is not, and a negative `ZOK_ERR_*`
+/// code on failure.
+///
+/// # Safety
+///
+/// Each pointer must be valid for its length.
+#[no_mangle]
+pub unsafe extern "C" fn zok_verify(
+    curve: c_int,
+    vk_ptr: *const u8,
+    vk_len: usize,
+    proof_ptr: *const u8,
+    proof_len: usize,
+    inputs_ptr: *const u8,
+    inputs_len: usize,
+) -> c_int {
+    guard(|| {
+        let vk = bytes(vk_ptr, vk_len)?;
+        let proof = bytes(proof_ptr, proof_len)?;
+        let inputs = bytes(inputs_ptr, inputs_len)?;
+
+        match curve {
+            ZOK_CURVE_BN128 => verify::<Bn128Field>(vk, proof, inputs),
+            ZOK_CURVE_BLS12_381 => verify::<Bls12Field>(vk, proof, inputs),
+            _ => Err(ZOK_ERR_CURVE),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+    use zokrates_core::ir::{Interpreter, ProgBuilder, QuadComb};
+    use zokrates_core::proof_system::bellman::groth16::{write_inputs, write_pvk};
+
+    #[test]
+    fn prove_verify() {
+        let mut builder = ProgBuilder::<Bn128Field>::new();
+        let a = builder.private_input();
+        let b = builder.variable();
+        builder.constrain(QuadComb::from_linear_combinations(a.into(), a.into()), b);
+        builder.output(b);
+        let program = builder.build().unwrap();
+
+        let witness = Interpreter::default()
+            .execute(&program, &vec![Bn128Field::from(3)])
+            .unwrap();
+        let computation = Computation::with_witness(program.clone(), witness.clone());
+        let inputs = computation.public_inputs_values();
+        let params = computation.setup();
+
+        let mut prog_bytes = vec![];
+        program.serialize(&mut prog_bytes);
+        let mut witness_bytes = vec![];
+        witness
+            .write_wtns_binary(&program, &mut witness_bytes)
+            .unwrap();
+        let mut params_bytes = vec![];
+        params.write(&mut params_bytes).unwrap();
+        let mut vk_bytes = vec![];
+        write_pvk::<Bn128Field, _>(&params.vk, &mut vk_bytes).unwrap();
+        let mut inputs_bytes = vec![];
+        write_inputs::<Bn128Field, _>(&inputs, &mut inputs_bytes).unwrap();
+
+        let prove = |out: &mut [u8], params: &[u8]| unsafe {
+            zok_prove(
+                prog_bytes.as_ptr(),
+                prog_bytes.len(),
+                witness_bytes.as_ptr(),
+                witness_bytes.len(),
+                params.as_ptr(),
+                params.len(),
+                out.as_mut_ptr(),
+                out.len(),
+            )
+        };
+
+        let mut out = vec![0u8; 1024];
+        let len = prove(&mut out, &params_bytes);
+        assert!(len > 0);
+        let proof = &out[..len as usize];
+
+        let verify = |proof: &[u8], inputs: &[u8]| unsafe {
+            zok_verify(
+                ZOK_CURVE_BN128,
+                vk_bytes.as_ptr(),
+                vk_bytes.len(),
+                proof.as_ptr(),
+                proof.len(),
+                inputs.as_ptr(),
+                inputs.len(),
+            )
+        };
+
+        assert_eq!(verify(proof, &inputs_bytes), 1);
+
+        let mut wrong_inputs = vec![];
+        write_inputs::<Bn128Field, _>(&[inputs[0], inputs[0]], &mut wrong_inputs).unwrap();
+        assert_eq!(verify(proof, &wrong_inputs), ZOK_ERR_INPUTS);
+
+        let mut other_inputs = vec![];
+        write_inputs::<Bn128Field, _>(&[Bn128Field::from(10).into_bellman()], &mut other_inputs)
+            .unwrap();
+        assert_eq!(verify(proof, &other_inputs), 0);
+
+        assert_eq!(verify(&proof[1..], &inputs_bytes), ZOK_ERR_PROOF);
+        assert_eq!(
+            unsafe {
+                zok_verify(
+                    2,
+                    vk_bytes.as_ptr(),
+                    vk_bytes.len(),
+                    proof.as_ptr(),
+                    proof.len(),
+                    inputs_bytes.as_ptr(),
+                    inputs_bytes.len(),
+                )
+            },
+            ZOK_ERR_CURVE
+        );
+        assert_eq!(
+            prove(&mut vec![0u8; len as usize - 1], &params_bytes),
+            ZOK_ERR_BUFFER_TOO_SMALL
+        );
+        assert_eq!(prove(&mut out, &params_bytes[1..]), ZOK_ERR_KEY);
+
+        assert_eq!(
+            unsafe {
+                zok_verify(
+                    ZOK_CURVE_BN128,
+                    ptr::null(),
+                    1,
+                    ptr::null(),
+                    0,
+                    ptr::null(),
+                    0,
+                )
+            },
+            ZOK_ERR_NULL_POINTER
+        );
+    }
+}
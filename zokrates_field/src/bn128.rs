@@ -278,6 +278,20 @@ mod tests {
             assert_eq!(fp, FieldPrime::from_byte_vector(bv));
         }
 
+        #[test]
+        fn modulus() {
+            let modulus = FieldPrime::modulus();
+            assert_eq!(
+                modulus.to_str_radix(10),
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+            );
+
+            let max = BigUint::from_bytes_be(&(modulus.clone() - 1u32).to_bytes_be());
+            assert_eq!(FieldPrime::from(max.clone()), FieldPrime::max_value());
+            assert_eq!(FieldPrime::max_value().to_biguint(), max);
+            assert_eq!(FieldPrime::from(modulus), FieldPrime::from(0));
+        }
+
         #[test]
         fn dec_string_ser_deser() {
             let fp = FieldPrime::from("101");
@@ -386,6 +400,29 @@ mod tests {
         use rand::{thread_rng, Rng};
         use Field;
 
+        #[test]
+        fn ct_eq() {
+            let rng = &mut thread_rng();
+            let random: Vec<FieldPrime> = (0..100)
+                .map(|_| FieldPrime::from_bellman(rng.gen::<Fr>()))
+                .collect();
+            let boundaries = vec![
+                FieldPrime::from(0),
+                FieldPrime::from(1),
+                FieldPrime::from(-1),
+                FieldPrime::from(u128::max_value()),
+                FieldPrime::from(u128::max_value()) + FieldPrime::from(1),
+                FieldPrime::max_value(),
+            ];
+
+            let values: Vec<_> = random.into_iter().chain(boundaries).collect();
+            for a in &values {
+                for b in &values {
+                    assert_eq!(a.ct_eq(b), a == b);
+                }
+            }
+        }
+
         #[test]
         fn fr_to_field_to_fr() {
             let rng = &mut thread_rng();
@@ -444,4 +481,53 @@ mod tests {
             assert_eq!(FieldPrime::from_bellman(a), cc);
         }
     }
+
+    mod parse {
+        use super::*;
+        use crate::ParseError;
+
+        #[test]
+        fn valid() {
+            assert_eq!("42".parse::<FieldPrime>(), Ok(FieldPrime::from(42)));
+            assert_eq!("-1".parse::<FieldPrime>(), Ok(FieldPrime::from(-1)));
+            assert_eq!(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495616"
+                    .parse::<FieldPrime>(),
+                Ok(FieldPrime::max_value())
+            );
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!("".parse::<FieldPrime>(), Err(ParseError::Empty));
+            assert_eq!("-".parse::<FieldPrime>(), Err(ParseError::Empty));
+        }
+
+        #[test]
+        fn invalid_digit() {
+            assert_eq!(
+                "12a4".parse::<FieldPrime>(),
+                Err(ParseError::InvalidDigit('a'))
+            );
+            assert_eq!(FieldPrime::try_from_dec_str("12a4"), Err(()));
+            // a single sign is allowed
+            assert_eq!(
+                "--5".parse::<FieldPrime>(),
+                Err(ParseError::InvalidDigit('-'))
+            );
+            assert_eq!(
+                "+-5".parse::<FieldPrime>(),
+                Err(ParseError::InvalidDigit('-'))
+            );
+        }
+
+        #[test]
+        fn overflow() {
+            assert_eq!(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+                    .parse::<FieldPrime>(),
+                Err(ParseError::Overflow)
+            );
+        }
+    }
 }
@@ -0,0 +1,120 @@
+//! Domain-separated hashing of arbitrary data to field elements, following `hash_to_field` from RFC 9380 with
+//! `expand_message_xmd` over SHA-256
+
+use crate::Field;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+// the security level in bits, which defines how many bytes are reduced modulo the prime to keep the bias negligible
+const SECURITY_BITS: usize = 128;
+
+/// Expands `msg` into `len` uniformly random bytes, using `dst` as the domain separation tag. Tags longer than 255
+/// bytes are first hashed, as prescribed by section 5.3.3 of RFC 9380.
+fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    assert!(len <= 255 * 32, "Requested too many bytes");
+
+    let dst = if dst.len() > 255 {
+        Sha256::new()
+            .chain(b"H2C-OVERSIZE-DST-")
+            .chain(dst)
+            .result()
+            .to_vec()
+    } else {
+        dst.to_vec()
+    };
+
+    let dst_prime: Vec<u8> = dst.iter().cloned().chain(Some(dst.len() as u8)).collect();
+
+    let b_0 = Sha256::new()
+        .chain(&[0u8; 64][..])
+        .chain(msg)
+        .chain((len as u16).to_be_bytes())
+        .chain([0u8])
+        .chain(&dst_prime)
+        .result();
+
+    let mut b_i = Sha256::new()
+        .chain(b_0)
+        .chain([1u8])
+        .chain(&dst_prime)
+        .result();
+
+    let mut res = b_i.to_vec();
+
+    for i in 2..=(len + 31) / 32 {
+        let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = Sha256::new()
+            .chain(xored)
+            .chain([i as u8])
+            .chain(&dst_prime)
+            .result();
+        res.extend(b_i.iter());
+    }
+
+    res.truncate(len);
+    res
+}
+
+/// Hashes `data` to an element of `T`, separating the outputs of different use cases with `domain`. Hashing the
+/// same data under two domains yields unrelated elements. The output is reduced and only depends on the inputs.
+pub fn hash_to_field<T: Field>(domain: &[u8], data: &[u8]) -> T {
+    let len = (T::get_required_bits() + SECURITY_BITS + 7) / 8;
+    let bytes = expand_message_xmd(data, domain, len);
+    T::from(BigUint::from_bytes_be(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bn128Field;
+
+    #[test]
+    fn expand_message() {
+        // test vector from RFC 9380, appendix K.1
+        assert_eq!(
+            expand_message_xmd(b"", b"QUUX-V01-CS02-with-expander-SHA256-128", 0x20),
+            vec![
+                0x68, 0xa9, 0x85, 0xb8, 0x7e, 0xb6, 0xb4, 0x69, 0x52, 0x12, 0x89, 0x11, 0xf2, 0xa4,
+                0x41, 0x2b, 0xbc, 0x30, 0x2a, 0x9d, 0x75, 0x96, 0x67, 0xf8, 0x7f, 0x7a, 0x21, 0xd8,
+                0x03, 0xf0, 0x72, 0x35
+            ]
+        );
+    }
+
+    #[test]
+    fn oversized_domain() {
+        let dst = vec![42u8; 256];
+        let hashed = Sha256::new()
+            .chain(b"H2C-OVERSIZE-DST-")
+            .chain(&dst)
+            .result();
+
+        assert_eq!(
+            expand_message_xmd(b"abc", &dst, 0x20),
+            expand_message_xmd(b"abc", &hashed, 0x20)
+        );
+        assert_eq!(
+            hash_to_field::<Bn128Field>(&dst, b"secret"),
+            hash_to_field::<Bn128Field>(&hashed, b"secret")
+        );
+    }
+
+    #[test]
+    fn hash() {
+        let nullifier = hash_to_field::<Bn128Field>(b"zokrates-nullifier", b"secret");
+        assert_eq!(
+            nullifier,
+            Bn128Field::try_from_dec_str(
+                "21583013859000155764022338697504144869124163035872203445118158036605566593855"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            nullifier,
+            hash_to_field::<Bn128Field>(b"zokrates-nullifier", b"secret")
+        );
+
+        let commitment = hash_to_field::<Bn128Field>(b"zokrates-commitment", b"secret");
+        assert_ne!(nullifier, commitment);
+    }
+}
@@ -10,9 +10,30 @@ use num_bigint::BigUint;
 use num_traits::{One, Zero};
 use serde::{Deserialize, Serialize};
 use std::convert::From;
+use std::fmt;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
+
+/// An error encountered while parsing a field element from a string
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    InvalidDigit(char),
+    /// The value is not strictly smaller than the field modulus
+    Overflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Empty => write!(f, "Cannot parse a field element from an empty string"),
+            ParseError::InvalidDigit(c) => write!(f, "Invalid digit `{}`", c),
+            ParseError::Overflow => write!(f, "Value does not fit in the field"),
+        }
+    }
+}
 
 pub trait Pow<RHS> {
     type Output;
@@ -50,6 +71,7 @@ pub trait Field:
     + Serialize
     + num_traits::CheckedAdd
     + num_traits::CheckedMul
+    + FromStr<Err = ParseError>
 {
     /// An associated type to be able to operate with Bellman ff traits
     type BellmanEngine: Engine;
@@ -77,6 +99,25 @@ pub trait Field:
     fn to_dec_string(&self) -> String;
     /// Returns the multiplicative inverse, i.e.: self * self.inverse_mul() = Self::one()
     fn inverse_mul(&self) -> Self;
+    /// Returns true if `self` equals `other`, comparing the full canonical representations so that the running time
+    /// does not depend on where they differ. To be used instead of `==` when one of the values is secret.
+    ///
+    /// The default implementation compares the byte vectors of the values, whose computation depends on the values
+    /// themselves, so implementations should rather compare fixed-width limbs.
+    fn ct_eq(&self, other: &Self) -> bool {
+        let len = (Self::get_required_bits() + 7) / 8;
+        let bytes = |f: &Self| {
+            let mut bytes = f.into_byte_vector();
+            bytes.resize(len, 0);
+            bytes
+        };
+
+        bytes(self)
+            .iter()
+            .zip(bytes(other).iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
     /// Returns the smallest value that can be represented by this field type.
     fn min_value() -> Self;
     /// Returns the largest value that can be represented by this field type.
@@ -86,6 +127,8 @@ pub trait Field:
     fn max_unique_value() -> Self;
     /// Returns the number of bits required to represent any element of this field type.
     fn get_required_bits() -> usize;
+    /// Returns the prime modulus of this field type.
+    fn modulus() -> BigUint;
     /// Tries to parse a string into this representation
     fn try_from_dec_str<'a>(s: &'a str) -> Result<Self, ()>;
     fn try_from_str(s: &str, radix: u32) -> Result<Self, ()>;
@@ -123,7 +166,7 @@ pub trait Field:
 mod prime_field {
     macro_rules! prime_field {
         ($modulus:expr, $bellman_type:ty, $fq2_type: ident, $name:expr) => {
-            use crate::{Field, Pow};
+            use crate::{Field, ParseError, Pow};
             use lazy_static::lazy_static;
             use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
             use num_integer::Integer;
@@ -133,6 +176,7 @@ mod prime_field {
             use std::fmt;
             use std::fmt::{Debug, Display};
             use std::ops::{Add, Div, Mul, Sub};
+            use std::str::FromStr;
 
             lazy_static! {
                 static ref P: BigInt = BigInt::parse_bytes($modulus, 10).unwrap();
@@ -168,6 +212,25 @@ mod prime_field {
                     }
                 }
 
+                fn ct_eq(&self, other: &Self) -> bool {
+                    // values are reduced and non-negative, so their 32-bit digits fit in as many limbs as the
+                    // modulus. The digits are copied as stored, and the comparison runs over all the limbs.
+                    let width = (P.bits() + 31) / 32;
+                    let limbs = |f: &FieldPrime| {
+                        let mut limbs = vec![0u32; width];
+                        for (limb, digit) in limbs.iter_mut().zip(f.value.to_u32_digits().1) {
+                            *limb = digit;
+                        }
+                        limbs
+                    };
+
+                    limbs(self)
+                        .iter()
+                        .zip(limbs(other).iter())
+                        .fold(0u32, |acc, (a, b)| acc | (a ^ b))
+                        == 0
+                }
+
                 fn from_byte_vector(bytes: Vec<u8>) -> Self {
                     let uval = BigUint::from_bytes_le(bytes.as_slice());
                     FieldPrime {
@@ -206,11 +269,14 @@ mod prime_field {
                 fn get_required_bits() -> usize {
                     (*P).bits()
                 }
+                fn modulus() -> BigUint {
+                    P.to_biguint().unwrap()
+                }
                 fn try_from_dec_str<'a>(s: &'a str) -> Result<Self, ()> {
                     Self::try_from_str(s, 10)
                 }
                 fn try_from_str(s: &str, radix: u32) -> Result<Self, ()> {
-                    let x = BigInt::parse_bytes(s.as_bytes(), radix).ok_or(())?;
+                    let x = parse_integer(s, radix).map_err(|_| ())?;
                     Ok(FieldPrime {
                         value: &x - x.div_floor(&*P) * &*P,
                     })
@@ -483,6 +549,38 @@ mod prime_field {
                 }
             }
 
+            /// Parses an integer with an optional sign, without reducing it
+            fn parse_integer(s: &str, radix: u32) -> Result<BigInt, ParseError> {
+                let digits = s
+                    .strip_prefix('-')
+                    .or_else(|| s.strip_prefix('+'))
+                    .unwrap_or(s);
+                if digits.is_empty() {
+                    return Err(ParseError::Empty);
+                }
+                if let Some(c) = digits.chars().find(|c| !c.is_digit(radix)) {
+                    return Err(ParseError::InvalidDigit(c));
+                }
+                BigInt::parse_bytes(s.as_bytes(), radix).ok_or(ParseError::Empty)
+            }
+
+            /// Parses a decimal value, failing if its absolute value is not smaller than the modulus
+            impl FromStr for FieldPrime {
+                type Err = ParseError;
+
+                fn from_str(s: &str) -> Result<Self, ParseError> {
+                    use num_traits::Signed;
+
+                    let x = parse_integer(s, 10)?;
+                    if x.abs() >= *P {
+                        return Err(ParseError::Overflow);
+                    }
+                    Ok(FieldPrime {
+                        value: &x - x.div_floor(&*P) * &*P,
+                    })
+                }
+            }
+
             /// Calculates the gcd using an iterative implementation of the extended euclidian algorithm.
             /// Returning `(d, s, t)` so that `d = s * a + t * b`
             ///
@@ -513,6 +611,8 @@ mod prime_field {
 
 pub mod bls12_381;
 pub mod bn128;
+mod hash_to_field;
 
 pub use bls12_381::FieldPrime as Bls12Field;
 pub use bn128::FieldPrime as Bn128Field;
+pub use hash_to_field::hash_to_field;
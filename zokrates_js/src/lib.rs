@@ -149,8 +149,11 @@ pub fn compute_witness(artifacts: JsValue, args: JsValue) -> Result<JsValue, JsV
     let return_values: serde_json::Value =
         zokrates_abi::CheckedValues::decode(witness.return_values(), signature.outputs).into();
 
+    let mut serialized_witness = vec![];
+    witness.write(&mut serialized_witness).unwrap();
+
     let result = ComputationResult {
-        witness: format!("{}", witness),
+        witness: String::from_utf8(serialized_witness).unwrap(),
         output: to_string_pretty(&return_values).unwrap(),
     };
 